@@ -27,6 +27,14 @@ pub fn set_callbacks(dialog_svc_connector: &mut DialogServiceConnector) {
         .set_session_stopped_cb(|event| info!(">set_session_stopped_cb {:?}", event))
         .unwrap();
 
+    dialog_svc_connector
+        .set_speech_start_detected_cb(|event| info!(">set_speech_start_detected_cb {:?}", event))
+        .unwrap();
+
+    dialog_svc_connector
+        .set_speech_end_detected_cb(|event| info!(">set_speech_end_detected_cb {:?}", event))
+        .unwrap();
+
     dialog_svc_connector
         .set_recognizing_cb(|event| info!(">set_recognizing_cb {:?}", event.result.text))
         .unwrap();
@@ -42,6 +50,10 @@ pub fn set_callbacks(dialog_svc_connector: &mut DialogServiceConnector) {
     dialog_svc_connector
         .set_activity_received_cb(|event| info!(">set_activity_received_cb {:?}", event))
         .unwrap();
+
+    dialog_svc_connector
+        .set_turn_status_received_cb(|event| info!(">set_turn_status_received_cb {:?}", event))
+        .unwrap();
 }
 
 pub fn push_file_into_stream(filename: &str, mut audio_push_stream: PushAudioInputStream) {
@@ -90,7 +102,5 @@ pub fn dialog_service_connector_from_audio_cfg(
         env::var("MSServiceRegion").unwrap(),
     )
     .unwrap();
-    let dialog_service_connector =
-        DialogServiceConnector::from_config(bot_fw_config, Some(audio_config)).unwrap();
-    dialog_service_connector
+    DialogServiceConnector::from_config(bot_fw_config, Some(audio_config)).unwrap()
 }
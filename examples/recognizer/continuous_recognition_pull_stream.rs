@@ -42,12 +42,7 @@ impl PullAudioInputStreamCallbacks for BinaryAudioStreamReader {
         // we need to extend internal_buffer to same length
         // as clone_from_slice requires both slices to have
         // same length
-        let diff = size - bytes_read;
-        if bytes_read < size {
-            for _ in 0..diff {
-                internal_buffer.push(0);
-            }
-        }
+        internal_buffer.resize(size, 0);
 
         trace!(
             "BinaryAudioStreamReader.internal_buffer {} {}",
@@ -63,7 +58,6 @@ impl PullAudioInputStreamCallbacks for BinaryAudioStreamReader {
     fn close(&mut self) {
         // nothing to do
         debug!("BinaryAudioStreamReader.close called");
-        return;
     }
 
     #[allow(unused_variables)]
@@ -6,6 +6,7 @@ mod from_microphone;
 mod helpers;
 mod recognize_once_async_from_file;
 mod recognize_once_async_from_push_stream;
+mod translation_with_voice_output;
 
 #[tokio::main]
 async fn main() {
@@ -19,6 +20,7 @@ async fn main() {
     continuous_recognition_push_stream::run_example().await;
     recognize_once_async_from_push_stream::run_example().await;
     continuous_recognition_pull_stream::run_example().await;
+    translation_with_voice_output::run_example().await;
     // works only on system with properly configured microphone
     // from_microphone::run_example().await;
 
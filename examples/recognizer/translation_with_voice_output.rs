@@ -0,0 +1,68 @@
+use super::helpers;
+use cognitive_services_speech_sdk_rs::audio::AudioConfig;
+use cognitive_services_speech_sdk_rs::common::ResultReason;
+use cognitive_services_speech_sdk_rs::translation::{
+    SpeechTranslationConfig, TranslationRecognizer,
+};
+use log::*;
+use std::env;
+use std::time::Duration;
+use tokio::time::sleep;
+
+#[allow(dead_code)]
+pub async fn run_example() {
+    info!("------------------------------------------------");
+    info!("running translation_with_voice_output example...");
+    info!("------------------------------------------------");
+
+    let filename = helpers::get_sample_file("turn_on_the_lamp.wav");
+    let audio_config = AudioConfig::from_wav_file_input(&filename).unwrap();
+
+    let mut translation_config = SpeechTranslationConfig::from_subscription(
+        env::var("MSSubscriptionKey").unwrap(),
+        env::var("MSServiceRegion").unwrap(),
+    )
+    .unwrap();
+    translation_config
+        .set_speech_recognition_language("en-US".to_string())
+        .unwrap();
+    translation_config.add_target_language("de").unwrap();
+    translation_config
+        .set_voice_name("de-DE-KatjaNeural".to_string())
+        .unwrap();
+
+    let mut translation_recognizer =
+        TranslationRecognizer::from_config(translation_config, audio_config).unwrap();
+
+    translation_recognizer
+        .set_recognized_cb(|event| info!(">set_recognized_cb {:?}", event.result.translations))
+        .unwrap();
+
+    translation_recognizer
+        .set_synthesizing_cb(|event| match event.result.reason {
+            ResultReason::SynthesizingAudio => info!(
+                ">set_synthesizing_cb got {} bytes of translated audio",
+                event.result.audio_data.len()
+            ),
+            _ => info!(">set_synthesizing_cb {:?}", event.result.reason),
+        })
+        .unwrap();
+
+    translation_recognizer
+        .set_canceled_cb(|event| info!(">set_canceled_cb {:?}", event))
+        .unwrap();
+
+    if let Err(err) = translation_recognizer
+        .start_continuous_recognition_async()
+        .await
+    {
+        error!("start_continuous_recognition_async error {:?}", err);
+    }
+    sleep(Duration::from_millis(10000)).await;
+    translation_recognizer
+        .stop_continuous_recognition_async()
+        .await
+        .unwrap();
+
+    info!("example finished!");
+}
@@ -140,8 +140,7 @@ pub fn speech_recognizer_from_audio_cfg(audio_config: AudioConfig) -> SpeechReco
         env::var("MSServiceRegion").unwrap(),
     )
     .unwrap();
-    let speech_recognizer = SpeechRecognizer::from_config(speech_config, audio_config).unwrap();
-    speech_recognizer
+    SpeechRecognizer::from_config(speech_config, audio_config).unwrap()
 }
 
 pub fn speech_recognizer_from_push_stream() -> (SpeechRecognizer, PushAudioInputStream) {
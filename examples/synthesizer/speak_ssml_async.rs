@@ -1,5 +1,6 @@
 use super::helpers;
 use cognitive_services_speech_sdk_rs::audio::PushAudioOutputStreamCallbacks;
+use cognitive_services_speech_sdk_rs::speech::ssml::{Speak, Voice};
 use log::*;
 use std::sync::mpsc;
 use std::time::Duration;
@@ -51,11 +52,13 @@ pub async fn run_example() {
 
     helpers::set_callbacks_all(&mut speech_synthesizer);
 
+    let ssml = Speak::new("en-US")
+        .voice(Voice::new("en-GB-George").text("This is sample SSML text to transcribe"))
+        .to_ssml()
+        .unwrap();
+
     let handle = tokio::spawn(async move {
-        if let Err(err) = speech_synthesizer
-            .speak_ssml_async("<speak xmlns='http://www.w3.org/2001/10/synthesis' xmlns:mstts='http://www.w3.org/2001/mstts' xmlns:emo='http://www.w3.org/2009/10/emotionml' version='1.0' xml:lang='en-US'><voice name='en-GB-George'>This is sample SSML text to transcribe</voice></speak>")
-            .await
-        {
+        if let Err(err) = speech_synthesizer.speak_ssml_async(&ssml).await {
             error!("speak_ssml_async error {:?}", err);
         }
         sleep(Duration::from_millis(10000)).await;
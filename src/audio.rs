@@ -1,23 +1,51 @@
 //! Package audio provides the audio configuration, input/output streams, and related utilities for audio interactions.
 mod audio_config;
+mod audio_converter;
 mod audio_input_stream;
 mod audio_output_stream;
+mod audio_recorder;
 mod audio_stream_container_format;
 mod audio_stream_format;
+mod bounded_push_audio_input_stream;
+mod buffer_properties;
 mod pull_audio_input_stream;
 mod pull_audio_output_stream;
 mod push_audio_input_stream;
 mod push_audio_output_stream;
+mod read_callbacks;
+mod voice_activity_gate;
+mod wav;
+mod wave_format;
+mod write_callbacks;
 
 // re-export structs directly under audio module
 pub use self::audio_config::AudioConfig;
+pub use self::audio_converter::{
+    ConvertingPushAudioInputStream, ConvertingReader, PcmConverter, PcmFormat, SampleFormat,
+};
 pub use self::audio_input_stream::AudioInputStream;
 pub use self::audio_output_stream::AudioOutputStream;
+pub use self::audio_recorder::{AudioRecorder, RecordingCallbacks, DEFAULT_MAX_PENDING_BYTES};
 pub use self::audio_stream_container_format::AudioStreamContainerFormat;
 pub use self::audio_stream_format::AudioStreamFormat;
+pub use self::bounded_push_audio_input_stream::{
+    BoundedPushAudioInputStream, PushAudioInputStreamMetrics,
+};
+pub use self::buffer_properties::{
+    BufferProperties, BufferPropertiesCallbacks, BufferTimeline, BufferTimestamp,
+    PullAudioInputStreamBufferCallbacks,
+};
 pub use self::pull_audio_input_stream::PullAudioInputStream;
 pub use self::pull_audio_input_stream::PullAudioInputStreamCallbacks;
 pub use self::pull_audio_output_stream::PullAudioOutputStream;
 pub use self::push_audio_input_stream::PushAudioInputStream;
 pub use self::push_audio_output_stream::PushAudioOutputStream;
 pub use self::push_audio_output_stream::PushAudioOutputStreamCallbacks;
+pub use self::read_callbacks::{AsyncReadCallbacks, ReadCallbacks};
+pub use self::voice_activity_gate::{
+    GatedCallbacks, GatedPushAudioInputStream, OffsetMap, VoiceActivityGate,
+    VoiceActivityGateConfig, VoiceActivityGateStats,
+};
+pub use self::wav::{WavHeader, WavReader, WavWriter, DEFAULT_WAV_CHUNK_SIZE};
+pub use self::wave_format::WaveFormat;
+pub use self::write_callbacks::{AsyncWriteCallbacks, SenderCallbacks, WriteCallbacks};
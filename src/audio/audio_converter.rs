@@ -0,0 +1,428 @@
+use crate::audio::{AudioInputStream, AudioStreamFormat, PushAudioInputStream};
+use crate::error::Result;
+use crate::ffi::SPXAUDIOSTREAMHANDLE;
+use std::f64::consts::PI;
+use std::io::{self, Read};
+
+/// Number of zero crossings of the resampling filter on each side of the sample.
+const RESAMPLER_ZERO_CROSSINGS: f64 = 8.0;
+
+/// SampleFormat defines encoding of single interleaved little-endian PCM sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat {
+    /// Unsigned 8 bit.
+    U8,
+    /// Signed 16 bit.
+    I16,
+    /// Signed 24 bit packed into 3 bytes.
+    I24,
+    /// Signed 32 bit.
+    I32,
+    /// 32 bit IEEE float in range -1.0..=1.0, e.g. Web Audio API output.
+    F32,
+}
+
+impl SampleFormat {
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            SampleFormat::U8 => 1,
+            SampleFormat::I16 => 2,
+            SampleFormat::I24 => 3,
+            SampleFormat::I32 | SampleFormat::F32 => 4,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            SampleFormat::U8 => (f32::from(bytes[0]) - 128.0) / 128.0,
+            SampleFormat::I16 => f32::from(i16::from_le_bytes([bytes[0], bytes[1]])) / 32768.0,
+            SampleFormat::I24 => {
+                (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / 8388608.0
+            }
+            SampleFormat::I32 => {
+                i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32 / 2147483648.0
+            }
+            SampleFormat::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+
+    fn encode(self, sample: f32, out: &mut Vec<u8>) {
+        let sample = f64::from(sample.clamp(-1.0, 1.0));
+        match self {
+            SampleFormat::U8 => out.push((sample * 127.0 + 128.0).round() as u8),
+            SampleFormat::I16 => {
+                out.extend_from_slice(&((sample * 32767.0).round() as i16).to_le_bytes())
+            }
+            SampleFormat::I24 => {
+                out.extend_from_slice(&((sample * 8388607.0).round() as i32).to_le_bytes()[..3])
+            }
+            SampleFormat::I32 => {
+                out.extend_from_slice(&((sample * 2147483647.0).round() as i32).to_le_bytes())
+            }
+            SampleFormat::F32 => out.extend_from_slice(&(sample as f32).to_le_bytes()),
+        }
+    }
+}
+
+/// PcmFormat describes raw interleaved PCM audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcmFormat {
+    pub samples_per_second: u32,
+    pub channels: u8,
+    pub sample_format: SampleFormat,
+}
+
+impl PcmFormat {
+    pub fn new(samples_per_second: u32, channels: u8, sample_format: SampleFormat) -> Self {
+        PcmFormat {
+            samples_per_second,
+            channels,
+            sample_format,
+        }
+    }
+
+    /// Format expected by recognizers, i.e. 16 kHz, 16 bit, mono.
+    pub fn default_input() -> Self {
+        PcmFormat::new(16000, 1, SampleFormat::I16)
+    }
+
+    pub fn bytes_per_frame(&self) -> usize {
+        self.sample_format.bytes_per_sample() * usize::from(self.channels)
+    }
+
+    /// Creates matching *AudioStreamFormat*. Float samples are not supported by the native API.
+    pub fn to_audio_stream_format(&self) -> Result<AudioStreamFormat> {
+        if self.sample_format == SampleFormat::F32 {
+            return Err(invalid_input("F32 samples are not supported by AudioStreamFormat").into());
+        }
+        let bits_per_sample = (self.sample_format.bytes_per_sample() * 8) as u8;
+        AudioStreamFormat::get_wave_format_pcm(
+            self.samples_per_second,
+            Some(bits_per_sample),
+            Some(self.channels),
+        )
+    }
+}
+
+/// PcmConverter converts PCM audio between sample formats, channel counts and
+/// sample rates. Channels are downmixed by averaging (or mono duplicated) and
+/// resampling uses windowed sinc interpolation which low-pass filters the signal
+/// when downsampling. Conversion is incremental, input can be split arbitrarily.
+#[derive(Debug)]
+pub struct PcmConverter {
+    source: PcmFormat,
+    target: PcmFormat,
+    /// Incomplete frame left over from the previous input.
+    partial: Vec<u8>,
+    /// Decoded and channel-mixed frames not yet consumed by the resampler.
+    frames: Vec<f32>,
+    /// Absolute index of the first frame in *frames*.
+    frames_base: u64,
+    /// Total number of source frames received.
+    frames_total: u64,
+    /// Number of target frames produced.
+    produced: u64,
+}
+
+impl PcmConverter {
+    pub fn new(source: PcmFormat, target: PcmFormat) -> Result<Self> {
+        if source.samples_per_second == 0 || target.samples_per_second == 0 {
+            return Err(invalid_input("sample rate must not be zero").into());
+        }
+        if source.channels == 0 || target.channels == 0 {
+            return Err(invalid_input("channel count must not be zero").into());
+        }
+        let supported =
+            source.channels == target.channels || target.channels == 1 || source.channels == 1;
+        if !supported {
+            return Err(invalid_input(&format!(
+                "unsupported channel conversion {} -> {}",
+                source.channels, target.channels
+            ))
+            .into());
+        }
+        Ok(PcmConverter {
+            source,
+            target,
+            partial: vec![],
+            frames: vec![],
+            frames_base: 0,
+            frames_total: 0,
+            produced: 0,
+        })
+    }
+
+    pub fn source(&self) -> PcmFormat {
+        self.source
+    }
+
+    pub fn target(&self) -> PcmFormat {
+        self.target
+    }
+
+    /// Converts next piece of source audio, returning converted audio available so far.
+    pub fn convert(&mut self, input: &[u8]) -> Vec<u8> {
+        self.push_frames(input);
+        let mut out = vec![];
+        self.resample(false, &mut out);
+        out
+    }
+
+    /// Converts audio buffered for resampling. Call once the source has ended.
+    pub fn flush(&mut self) -> Vec<u8> {
+        self.partial.clear();
+        let mut out = vec![];
+        self.resample(true, &mut out);
+        out
+    }
+
+    fn push_frames(&mut self, input: &[u8]) {
+        let frame_len = self.source.bytes_per_frame();
+        let sample_format = self.source.sample_format;
+        let sample_len = sample_format.bytes_per_sample();
+        let source_channels = usize::from(self.source.channels);
+        let target_channels = usize::from(self.target.channels);
+
+        self.partial.extend_from_slice(input);
+        let whole = self.partial.len() / frame_len * frame_len;
+        for frame in self.partial[..whole].chunks_exact(frame_len) {
+            let mut samples = frame
+                .chunks_exact(sample_len)
+                .map(|sample| sample_format.decode(sample));
+            if source_channels == target_channels {
+                self.frames.extend(samples);
+            } else if target_channels == 1 {
+                let sum: f32 = samples.sum();
+                self.frames.push(sum / source_channels as f32);
+            } else {
+                let sample = samples.next().unwrap_or(0.0);
+                self.frames
+                    .extend(std::iter::repeat_n(sample, target_channels));
+            }
+            self.frames_total += 1;
+        }
+        self.partial.drain(..whole);
+    }
+
+    fn resample(&mut self, flush: bool, out: &mut Vec<u8>) {
+        let channels = usize::from(self.target.channels);
+        let sample_format = self.target.sample_format;
+
+        if self.source.samples_per_second == self.target.samples_per_second {
+            for sample in self.frames.drain(..) {
+                sample_format.encode(sample, out);
+            }
+            self.frames_base = self.frames_total;
+            self.produced = self.frames_total;
+            return;
+        }
+
+        let ratio =
+            f64::from(self.source.samples_per_second) / f64::from(self.target.samples_per_second);
+        // cutoff relative to source Nyquist frequency, lowered when downsampling
+        let scale = (1.0 / ratio).min(1.0);
+        let half_width = RESAMPLER_ZERO_CROSSINGS / scale;
+
+        loop {
+            let center = self.produced as f64 * ratio;
+            if center >= self.frames_total as f64 {
+                break;
+            }
+            if !flush && center + half_width >= self.frames_total as f64 {
+                break;
+            }
+            let first = (center - half_width).ceil().max(0.0) as u64;
+            let last = ((center + half_width).floor() as u64).min(self.frames_total - 1);
+            for channel in 0..channels {
+                let mut acc = 0.0;
+                for index in first.max(self.frames_base)..=last {
+                    let x = center - index as f64;
+                    let sample =
+                        self.frames[(index - self.frames_base) as usize * channels + channel];
+                    acc += f64::from(sample) * kernel(x, scale, half_width);
+                }
+                sample_format.encode(acc as f32, out);
+            }
+            self.produced += 1;
+        }
+
+        // drop frames no longer reachable by the filter
+        let needed = (self.produced as f64 * ratio - half_width).floor().max(0.0) as u64;
+        if needed > self.frames_base {
+            let drop = (needed - self.frames_base).min(self.frames_total - self.frames_base);
+            self.frames.drain(..drop as usize * channels);
+            self.frames_base += drop;
+        }
+    }
+}
+
+/// Blackman windowed sinc.
+fn kernel(x: f64, scale: f64, half_width: f64) -> f64 {
+    if x.abs() >= half_width {
+        return 0.0;
+    }
+    let t = PI * x * scale;
+    let sinc = if t.abs() < 1e-9 { 1.0 } else { t.sin() / t };
+    let w = PI * (x / half_width + 1.0);
+    let window = 0.42 - 0.5 * w.cos() + 0.08 * (2.0 * w).cos();
+    scale * sinc * window
+}
+
+/// ConvertingPushAudioInputStream wraps *PushAudioInputStream* so that callers
+/// can write audio in its native format (e.g. 48 kHz stereo f32 from browser),
+/// which is converted to the format of the underlying stream.
+#[derive(Debug)]
+pub struct ConvertingPushAudioInputStream {
+    pub stream: PushAudioInputStream,
+    converter: PcmConverter,
+}
+
+impl AudioInputStream for ConvertingPushAudioInputStream {
+    fn get_handle(&self) -> SPXAUDIOSTREAMHANDLE {
+        self.stream.get_handle()
+    }
+}
+
+impl ConvertingPushAudioInputStream {
+    /// Creates push stream accepting audio in *source* format
+    /// and converting it to 16 kHz, 16 bit, mono.
+    pub fn create_push_stream(source: PcmFormat) -> Result<Self> {
+        Self::create_push_stream_with_target(source, PcmFormat::default_input())
+    }
+
+    pub fn create_push_stream_with_target(source: PcmFormat, target: PcmFormat) -> Result<Self> {
+        let converter = PcmConverter::new(source, target)?;
+        let stream =
+            PushAudioInputStream::create_push_stream_from_format(target.to_audio_stream_format()?)?;
+        Ok(ConvertingPushAudioInputStream { stream, converter })
+    }
+
+    pub fn write(&mut self, buffer: impl AsRef<[u8]>) -> Result<()> {
+        let converted = self.converter.convert(buffer.as_ref());
+        if !converted.is_empty() {
+            self.stream.write(converted)?;
+        }
+        Ok(())
+    }
+
+    /// Writes audio still buffered by the resampler and closes the stream.
+    pub fn close_stream(&mut self) -> Result<()> {
+        let converted = self.converter.flush();
+        if !converted.is_empty() {
+            self.stream.write(converted)?;
+        }
+        self.stream.close_stream()
+    }
+}
+
+/// ConvertingReader converts audio read from underlying reader,
+/// e.g. to feed pull audio input stream from source in native format.
+#[derive(Debug)]
+pub struct ConvertingReader<R> {
+    reader: R,
+    converter: PcmConverter,
+    buffer: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<R: Read> ConvertingReader<R> {
+    pub fn new(reader: R, source: PcmFormat, target: PcmFormat) -> Result<Self> {
+        Ok(ConvertingReader {
+            reader,
+            converter: PcmConverter::new(source, target)?,
+            buffer: vec![],
+            position: 0,
+            finished: false,
+        })
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Read for ConvertingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.buffer.len() {
+            if self.finished || buf.is_empty() {
+                return Ok(0);
+            }
+            let mut input = vec![0u8; buf.len().max(self.converter.source.bytes_per_frame())];
+            let n = self.reader.read(&mut input)?;
+            self.buffer = if n == 0 {
+                self.finished = true;
+                self.converter.flush()
+            } else {
+                self.converter.convert(&input[..n])
+            };
+            self.position = 0;
+        }
+        let n = buf.len().min(self.buffer.len() - self.position);
+        buf[..n].copy_from_slice(&self.buffer[self.position..self.position + n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+fn invalid_input(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_i16(bytes: &[u8]) -> Vec<i16> {
+        bytes
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn converts_48k_stereo_f32_to_16k_mono_i16() {
+        let source = PcmFormat::new(48000, 2, SampleFormat::F32);
+        let mut converter = PcmConverter::new(source, PcmFormat::default_input()).unwrap();
+        let mut input = vec![];
+        for _ in 0..4800 {
+            input.extend_from_slice(&0.25f32.to_le_bytes());
+            input.extend_from_slice(&0.75f32.to_le_bytes());
+        }
+        let mut out = vec![];
+        // split input at odd offsets to exercise partial frames
+        for piece in input.chunks(1001) {
+            out.extend(converter.convert(piece));
+        }
+        out.extend(converter.flush());
+        let samples = decode_i16(&out);
+        assert_eq!(samples.len(), 1600);
+        // DC level 0.5 away from edges
+        assert!(samples[100..1500]
+            .iter()
+            .all(|s| (i32::from(*s) - 16384).abs() < 50));
+    }
+
+    #[test]
+    fn rejects_zero_channels_and_rates() {
+        let zero_channels = PcmFormat::new(16000, 0, SampleFormat::I16);
+        assert!(PcmConverter::new(zero_channels, zero_channels).is_err());
+        let zero_rate = PcmFormat::new(0, 1, SampleFormat::I16);
+        assert!(PcmConverter::new(zero_rate, PcmFormat::default_input()).is_err());
+    }
+
+    #[test]
+    fn same_rate_converts_format_and_channels() {
+        let source = PcmFormat::new(16000, 1, SampleFormat::U8);
+        let target = PcmFormat::new(16000, 2, SampleFormat::I24);
+        let mut converter = PcmConverter::new(source, target).unwrap();
+        let out = converter.convert(&[128, 255]);
+        assert_eq!(out.len(), 12);
+        assert_eq!(&out[..6], &[0, 0, 0, 0, 0, 0]);
+        assert!(PcmConverter::new(
+            PcmFormat::new(16000, 2, SampleFormat::I16),
+            PcmFormat::new(16000, 3, SampleFormat::I16)
+        )
+        .is_err());
+    }
+}
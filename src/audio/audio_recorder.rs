@@ -0,0 +1,354 @@
+use crate::audio::{AudioStreamFormat, PullAudioInputStreamCallbacks};
+use crate::audio::{WavHeader, WavWriter};
+use crate::error::Result;
+use crate::speech::SessionEvent;
+use log::*;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default limit of audio buffered while session id is not known (about 30 s of 16 kHz 16 bit mono).
+pub const DEFAULT_MAX_PENDING_BYTES: usize = 1024 * 1024;
+
+/// AudioRecorder mirrors every byte fed to the recognizer into local WAV file(s) or
+/// custom sink, so that the exact audio seen by the recognizer can be inspected later.
+/// Attach it to input stream via *PushAudioInputStream::set_recorder* or *RecordingCallbacks*
+/// and pass *session_started_cb* to *set_session_started_cb* of the recognizer (or call
+/// *set_session_id* from your own callback) so that recordings are named by session id.
+/// The stream and the session callback each hold a clone writing into the same file.
+/// Recording errors are logged and never interrupt the recognition.
+#[derive(Clone)]
+pub struct AudioRecorder {
+    state: Arc<Mutex<RecorderState>>,
+}
+
+impl fmt::Debug for AudioRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AudioRecorder").finish()
+    }
+}
+
+enum RecorderTarget {
+    Directory { directory: PathBuf, prefix: String },
+    Sink(Box<dyn Write + Send>),
+}
+
+enum RecordingFile {
+    Wav(WavWriter<BufWriter<File>>),
+    Raw(BufWriter<File>),
+}
+
+struct RecorderState {
+    target: RecorderTarget,
+    /// *None* for compressed formats which are recorded as is.
+    header: Option<WavHeader>,
+    file: Option<RecordingFile>,
+    /// Audio received before session id was known.
+    pending: Vec<u8>,
+    max_pending: usize,
+}
+
+impl AudioRecorder {
+    /// Records audio into *directory*, one file per session named *{prefix}{session_id}.wav*
+    /// (or *.raw* for compressed formats). Audio arriving before session id is set
+    /// is buffered and written into the file of the following session. When the buffer
+    /// exceeds *with_max_pending_bytes* it is written into *{prefix}unknown-session-{time}* file.
+    pub fn to_directory(
+        directory: impl Into<PathBuf>,
+        prefix: &str,
+        format: &AudioStreamFormat,
+    ) -> Result<Self> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)?;
+        Ok(Self::new(
+            RecorderTarget::Directory {
+                directory,
+                prefix: prefix.to_owned(),
+            },
+            WavHeader::from_audio_stream_format(format),
+        ))
+    }
+
+    /// Records raw audio bytes into *sink*, without any header.
+    pub fn to_sink(sink: Box<dyn Write + Send>) -> Self {
+        Self::new(RecorderTarget::Sink(sink), None)
+    }
+
+    fn new(target: RecorderTarget, header: Option<WavHeader>) -> Self {
+        AudioRecorder {
+            state: Arc::new(Mutex::new(RecorderState {
+                target,
+                header,
+                file: None,
+                pending: vec![],
+                max_pending: DEFAULT_MAX_PENDING_BYTES,
+            })),
+        }
+    }
+
+    /// Limits audio buffered while session id is not known, see *to_directory*.
+    pub fn with_max_pending_bytes(self, max_pending: usize) -> Self {
+        self.lock().max_pending = max_pending;
+        self
+    }
+
+    /// Returns callback for *set_session_started_cb* of the recognizer
+    /// which starts recording of each new session.
+    pub fn session_started_cb(&self) -> impl Fn(SessionEvent) + Send + 'static {
+        let recorder = self.clone();
+        move |event| recorder.set_session_id(&event.session_id)
+    }
+
+    /// Starts recording of the new session. Recording of the previous session (if any) is finalized.
+    pub fn set_session_id(&self, session_id: &str) {
+        let mut state = self.lock();
+        if let Err(err) = state.start_file(session_id) {
+            error!(
+                "AudioRecorder::set_session_id error for session {}: {}",
+                session_id, err
+            );
+        }
+    }
+
+    /// Mirrors audio fed to the recognizer.
+    pub fn write(&self, data: &[u8]) {
+        let mut state = self.lock();
+        if let Err(err) = state.write(data) {
+            error!("AudioRecorder::write error: {}", err);
+        }
+    }
+
+    /// Finalizes current recording (patches WAV header, flushes sink).
+    /// Buffered audio of unknown session is written into file named by current time.
+    pub fn finish(&self) -> Result<()> {
+        let mut state = self.lock();
+        if !state.pending.is_empty() {
+            state.start_unknown_session_file()?;
+        }
+        state.finish_file()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RecorderState> {
+        // recording state stays consistent even if other thread panicked while holding the lock
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl RecorderState {
+    fn start_unknown_session_file(&mut self) -> Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or_default();
+        self.start_file(&format!("unknown-session-{}", timestamp))
+    }
+
+    fn start_file(&mut self, session_id: &str) -> Result<()> {
+        self.finish_file()?;
+        let (directory, prefix) = match &self.target {
+            RecorderTarget::Directory { directory, prefix } => (directory, prefix),
+            RecorderTarget::Sink(_) => {
+                trace!("AudioRecorder recording session {} into sink", session_id);
+                return Ok(());
+            }
+        };
+        // session ids are GUIDs but make sure they cannot escape the directory
+        let session_id: String = session_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let extension = if self.header.is_some() { "wav" } else { "raw" };
+        let path = directory.join(format!("{}{}.{}", prefix, session_id, extension));
+        info!("AudioRecorder recording into {}", path.display());
+        let writer = BufWriter::new(File::create(&path)?);
+        let mut file = match &self.header {
+            Some(header) => RecordingFile::Wav(WavWriter::new(writer, header)?),
+            None => RecordingFile::Raw(writer),
+        };
+        let pending = std::mem::take(&mut self.pending);
+        file.write(&pending)?;
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        if let RecorderTarget::Sink(sink) = &mut self.target {
+            sink.write_all(data)?;
+            return Ok(());
+        }
+        if self.file.is_none() && self.pending.len() + data.len() > self.max_pending {
+            warn!(
+                "AudioRecorder session id not set, spilling {} buffered bytes into file",
+                self.pending.len()
+            );
+            self.start_unknown_session_file()?;
+        }
+        match self.file.as_mut() {
+            Some(file) => file.write(data),
+            None => {
+                self.pending.extend_from_slice(data);
+                Ok(())
+            }
+        }
+    }
+
+    fn finish_file(&mut self) -> Result<()> {
+        if let RecorderTarget::Sink(sink) = &mut self.target {
+            sink.flush()?;
+        }
+        match self.file.take() {
+            Some(RecordingFile::Wav(writer)) => {
+                writer.finalize()?;
+            }
+            Some(RecordingFile::Raw(mut writer)) => writer.flush()?,
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+impl RecordingFile {
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        match self {
+            RecordingFile::Wav(writer) => writer.write_samples(data),
+            RecordingFile::Raw(writer) => Ok(writer.write_all(data)?),
+        }
+    }
+}
+
+impl Drop for RecorderState {
+    fn drop(&mut self) {
+        if !self.pending.is_empty() {
+            if let Err(err) = self.start_unknown_session_file() {
+                error!("AudioRecorder pending audio error: {}", err);
+            }
+        }
+        if let Err(err) = self.finish_file() {
+            error!("AudioRecorder finalize error: {}", err);
+        }
+    }
+}
+
+/// RecordingCallbacks wraps *PullAudioInputStreamCallbacks* and mirrors
+/// all audio read by the recognizer into *AudioRecorder*.
+pub struct RecordingCallbacks {
+    inner: Box<dyn PullAudioInputStreamCallbacks>,
+    recorder: AudioRecorder,
+}
+
+impl fmt::Debug for RecordingCallbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordingCallbacks")
+            .field("recorder", &self.recorder)
+            .finish()
+    }
+}
+
+impl RecordingCallbacks {
+    pub fn new(inner: Box<dyn PullAudioInputStreamCallbacks>, recorder: AudioRecorder) -> Self {
+        RecordingCallbacks { inner, recorder }
+    }
+}
+
+impl PullAudioInputStreamCallbacks for RecordingCallbacks {
+    fn read(&mut self, data_buffer: &mut [u8]) -> u32 {
+        let n = self.inner.read(data_buffer);
+        self.recorder.write(&data_buffer[..n as usize]);
+        n
+    }
+
+    fn close(&mut self) {
+        self.inner.close();
+        if let Err(err) = self.recorder.finish() {
+            error!("RecordingCallbacks::close recorder error: {}", err);
+        }
+    }
+
+    fn get_property(&mut self, id: i32) -> Result<String> {
+        self.inner.get_property(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{WavReader, WaveFormat};
+    use std::io::Read;
+
+    fn directory_recorder(directory: &std::path::Path) -> AudioRecorder {
+        AudioRecorder::new(
+            RecorderTarget::Directory {
+                directory: directory.to_path_buf(),
+                prefix: "rec-".into(),
+            },
+            Some(WavHeader::new(WaveFormat::Pcm, 16000, 16, 1)),
+        )
+    }
+
+    fn samples(path: &std::path::Path) -> Vec<u8> {
+        let mut data = vec![];
+        WavReader::new(File::open(path).unwrap())
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        data
+    }
+
+    fn recordings(directory: &std::path::Path) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn records_sessions_and_buffered_audio() {
+        let directory = std::env::temp_dir().join(format!("recorder_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let recorder = directory_recorder(&directory);
+        recorder.write(&[1, 2]);
+        recorder.set_session_id("a/b");
+        recorder.write(&[3, 4]);
+        recorder.set_session_id("second");
+        recorder.write(&[5, 6]);
+        recorder.finish().unwrap();
+        assert_eq!(samples(&directory.join("rec-a_b.wav")), vec![1, 2, 3, 4]);
+        assert_eq!(samples(&directory.join("rec-second.wav")), vec![5, 6]);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        // without session id audio is spilled over the limit and flushed on drop
+        std::fs::create_dir_all(&directory).unwrap();
+        let recorder = directory_recorder(&directory).with_max_pending_bytes(4);
+        recorder.write(&[1, 2, 3]);
+        recorder.write(&[4, 5]);
+        drop(recorder);
+        let paths = recordings(&directory);
+        assert_eq!(paths.len(), 1);
+        assert_eq!(samples(&paths[0]), vec![1, 2, 3, 4, 5]);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        std::fs::create_dir_all(&directory).unwrap();
+        let recorder = directory_recorder(&directory);
+        recorder.write(&[7, 8]);
+        drop(recorder);
+        let paths = recordings(&directory);
+        assert_eq!(paths.len(), 1);
+        assert_eq!(samples(&paths[0]), vec![7, 8]);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(warnings)]
 /// AudioStreamContainerFormat defines supported audio stream container format.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AudioStreamContainerFormat {
     OggOpus = 257,
     Mp3 = 258,
@@ -1,10 +1,10 @@
-use crate::audio::AudioStreamContainerFormat;
+use crate::audio::{AudioStreamContainerFormat, WaveFormat};
 use crate::error::{convert_err, Result};
 use crate::ffi::{
     audio_stream_format_create_from_compressed_format,
     audio_stream_format_create_from_default_input, audio_stream_format_create_from_default_output,
-    audio_stream_format_create_from_waveformat_pcm, audio_stream_format_release, SmartHandle,
-    SPXAUDIOSTREAMFORMATHANDLE,
+    audio_stream_format_create_from_waveformat, audio_stream_format_create_from_waveformat_pcm,
+    audio_stream_format_release, SmartHandle, SPXAUDIOSTREAMFORMATHANDLE,
 };
 use std::mem::MaybeUninit;
 
@@ -13,17 +13,43 @@ use std::mem::MaybeUninit;
 pub struct AudioStreamFormat {
     /// Smart handle wrapping underlying SPXAUDIOSTREAMFORMATHANDLE
     pub handle: SmartHandle<SPXAUDIOSTREAMFORMATHANDLE>,
+    kind: AudioStreamFormatKind,
+}
+
+/// Parameters the format was created with. Native API
+/// does not provide getters so they are kept on Rust side.
+#[derive(Debug, Clone, Copy)]
+enum AudioStreamFormatKind {
+    Wave {
+        samples_per_second: u32,
+        bits_per_sample: u8,
+        channels: u8,
+        wave_format: WaveFormat,
+    },
+    Compressed(AudioStreamContainerFormat),
 }
 
 impl AudioStreamFormat {
     /// # Safety
     /// `handle` must be a valid handle to a live audio stream format.
-    unsafe fn from_handle(handle: SPXAUDIOSTREAMFORMATHANDLE) -> Result<AudioStreamFormat> {
+    unsafe fn from_handle(
+        handle: SPXAUDIOSTREAMFORMATHANDLE,
+        kind: AudioStreamFormatKind,
+    ) -> Result<AudioStreamFormat> {
         Ok(AudioStreamFormat {
             handle: SmartHandle::create("AudioStreamFormat", handle, audio_stream_format_release),
+            kind,
         })
     }
 
+    /// Default format of the input/output streams, i.e. 16 kHz, 16 bit, mono PCM.
+    const DEFAULT_KIND: AudioStreamFormatKind = AudioStreamFormatKind::Wave {
+        samples_per_second: 16000,
+        bits_per_sample: 16,
+        channels: 1,
+        wave_format: WaveFormat::Pcm,
+    };
+
     /// GetWaveFormatPCM creates an audio stream format object with the specified PCM waveformat characteristics.
     /// Note: Currently, only WAV / PCM with 16-bit samples, 16 kHz sample rate, and a single channel (Mono) is supported. When
     /// used with Conversation Transcription, eight channels are supported.
@@ -32,16 +58,56 @@ impl AudioStreamFormat {
         bits_per_sample: Option<u8>,
         channels: Option<u8>,
     ) -> Result<AudioStreamFormat> {
+        let bits_per_sample = bits_per_sample.unwrap_or(16);
+        let channels = channels.unwrap_or(1);
         unsafe {
             let mut handle: MaybeUninit<SPXAUDIOSTREAMFORMATHANDLE> = MaybeUninit::uninit();
             let ret = audio_stream_format_create_from_waveformat_pcm(
                 handle.as_mut_ptr(),
                 samples_per_second,
-                bits_per_sample.unwrap_or(16),
-                channels.unwrap_or(1),
+                bits_per_sample,
+                channels,
             );
             convert_err(ret, "AudioStreamFormat::get_wave_format_pcm error")?;
-            AudioStreamFormat::from_handle(handle.assume_init())
+            AudioStreamFormat::from_handle(
+                handle.assume_init(),
+                AudioStreamFormatKind::Wave {
+                    samples_per_second,
+                    bits_per_sample,
+                    channels,
+                    wave_format: WaveFormat::Pcm,
+                },
+            )
+        }
+    }
+
+    /// GetWaveFormat creates an audio stream format object with the specified waveformat characteristics,
+    /// e.g. 8 kHz, 8 bit, mono *WaveFormat::Mulaw* as received from telephony.
+    pub fn get_wave_format(
+        samples_per_second: u32,
+        bits_per_sample: u8,
+        channels: u8,
+        wave_format: WaveFormat,
+    ) -> Result<AudioStreamFormat> {
+        unsafe {
+            let mut handle: MaybeUninit<SPXAUDIOSTREAMFORMATHANDLE> = MaybeUninit::uninit();
+            let ret = audio_stream_format_create_from_waveformat(
+                handle.as_mut_ptr(),
+                samples_per_second,
+                bits_per_sample,
+                channels,
+                wave_format.into(),
+            );
+            convert_err(ret, "AudioStreamFormat::get_wave_format error")?;
+            AudioStreamFormat::from_handle(
+                handle.assume_init(),
+                AudioStreamFormatKind::Wave {
+                    samples_per_second,
+                    bits_per_sample,
+                    channels,
+                    wave_format,
+                },
+            )
         }
     }
 
@@ -52,7 +118,7 @@ impl AudioStreamFormat {
             let mut handle: MaybeUninit<SPXAUDIOSTREAMFORMATHANDLE> = MaybeUninit::uninit();
             let ret = audio_stream_format_create_from_default_input(handle.as_mut_ptr());
             convert_err(ret, "AudioStreamFormat::get_default_format error")?;
-            AudioStreamFormat::from_handle(handle.assume_init())
+            AudioStreamFormat::from_handle(handle.assume_init(), Self::DEFAULT_KIND)
         }
     }
 
@@ -63,7 +129,7 @@ impl AudioStreamFormat {
             let mut handle: MaybeUninit<SPXAUDIOSTREAMFORMATHANDLE> = MaybeUninit::uninit();
             let ret = audio_stream_format_create_from_default_output(handle.as_mut_ptr());
             convert_err(ret, "AudioStreamFormat::get_default_output_format error")?;
-            AudioStreamFormat::from_handle(handle.assume_init())
+            AudioStreamFormat::from_handle(handle.assume_init(), Self::DEFAULT_KIND)
         }
     }
 
@@ -79,7 +145,54 @@ impl AudioStreamFormat {
                 compressed_format.into(),
             );
             convert_err(ret, "AudioStreamFormat::get_compressed_format error")?;
-            AudioStreamFormat::from_handle(handle.assume_init())
+            AudioStreamFormat::from_handle(
+                handle.assume_init(),
+                AudioStreamFormatKind::Compressed(compressed_format),
+            )
+        }
+    }
+
+    /// Returns sample rate in Hz, *None* for compressed formats.
+    pub fn get_samples_per_second(&self) -> Option<u32> {
+        match self.kind {
+            AudioStreamFormatKind::Wave {
+                samples_per_second, ..
+            } => Some(samples_per_second),
+            AudioStreamFormatKind::Compressed(_) => None,
+        }
+    }
+
+    /// Returns number of bits per sample, *None* for compressed formats.
+    pub fn get_bits_per_sample(&self) -> Option<u8> {
+        match self.kind {
+            AudioStreamFormatKind::Wave {
+                bits_per_sample, ..
+            } => Some(bits_per_sample),
+            AudioStreamFormatKind::Compressed(_) => None,
+        }
+    }
+
+    /// Returns number of channels, *None* for compressed formats.
+    pub fn get_channels(&self) -> Option<u8> {
+        match self.kind {
+            AudioStreamFormatKind::Wave { channels, .. } => Some(channels),
+            AudioStreamFormatKind::Compressed(_) => None,
+        }
+    }
+
+    /// Returns wave format tag, *None* for compressed formats.
+    pub fn get_wave_format_tag(&self) -> Option<WaveFormat> {
+        match self.kind {
+            AudioStreamFormatKind::Wave { wave_format, .. } => Some(wave_format),
+            AudioStreamFormatKind::Compressed(_) => None,
+        }
+    }
+
+    /// Returns container format if the format was created by *get_compressed_format*.
+    pub fn get_container_format(&self) -> Option<AudioStreamContainerFormat> {
+        match self.kind {
+            AudioStreamFormatKind::Wave { .. } => None,
+            AudioStreamFormatKind::Compressed(container_format) => Some(container_format),
         }
     }
 }
@@ -0,0 +1,284 @@
+use crate::audio::{
+    AudioInputStream, AudioStreamFormat, PullAudioInputStream, PullAudioInputStreamCallbacks,
+};
+use crate::error::Result;
+use crate::ffi::SPXAUDIOSTREAMHANDLE;
+use log::*;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// Snapshot of *BoundedPushAudioInputStream* metrics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PushAudioInputStreamMetrics {
+    /// Bytes written by the producer and not yet read by the recognizer.
+    pub queued_bytes: u64,
+    /// Duration of queued audio.
+    pub queued_duration: Duration,
+    /// Total bytes written by the producer.
+    pub bytes_written: u64,
+    /// Total bytes read by the recognizer.
+    pub bytes_consumed: u64,
+    /// Duration of audio read by the recognizer divided by wall-clock time since
+    /// its first read. Values below 1.0 mean recognizer is falling behind real time.
+    /// *None* until the recognizer starts reading.
+    pub real_time_factor: Option<f64>,
+}
+
+struct QueueState {
+    queue: VecDeque<u8>,
+    closed: bool,
+    bytes_written: u64,
+    bytes_consumed: u64,
+    first_read: Option<Instant>,
+}
+
+struct Shared {
+    capacity_bytes: usize,
+    state: Mutex<QueueState>,
+    /// Signalled to the recognizer (native thread) when data are written or stream is closed.
+    data_available: Condvar,
+    /// Signalled to asynchronous writers when recognizer frees capacity or stream is closed.
+    space_available: Notify,
+}
+
+impl std::fmt::Debug for Shared {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Shared")
+            .field("capacity_bytes", &self.capacity_bytes)
+            .finish()
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn new(capacity_bytes: usize) -> Self {
+        Shared {
+            capacity_bytes,
+            state: Mutex::new(QueueState {
+                queue: VecDeque::with_capacity(capacity_bytes),
+                closed: false,
+                bytes_written: 0,
+                bytes_consumed: 0,
+                first_read: None,
+            }),
+            data_available: Condvar::new(),
+            space_available: Notify::new(),
+        }
+    }
+
+    fn close(&self) {
+        self.lock().closed = true;
+        self.data_available.notify_all();
+        self.space_available.notify_waiters();
+    }
+
+    async fn write(&self, mut buffer: &[u8]) -> Result<()> {
+        while !buffer.is_empty() {
+            let notified = self.space_available.notified();
+            tokio::pin!(notified);
+            // register interest before checking capacity so that no wake up is missed
+            notified.as_mut().enable();
+
+            let written = self.try_write(buffer)?;
+            buffer = &buffer[written..];
+            if written == 0 {
+                notified.await;
+            }
+        }
+        Ok(())
+    }
+
+    fn try_write(&self, buffer: &[u8]) -> Result<usize> {
+        let mut state = self.lock();
+        if state.closed {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "BoundedPushAudioInputStream is closed",
+            )
+            .into());
+        }
+        let n = buffer
+            .len()
+            .min(self.capacity_bytes.saturating_sub(state.queue.len()));
+        state.queue.extend(&buffer[..n]);
+        state.bytes_written += n as u64;
+        drop(state);
+        if n > 0 {
+            self.data_available.notify_all();
+        }
+        Ok(n)
+    }
+}
+
+/// BoundedPushAudioInputStream is push style input stream which limits amount of buffered audio.
+/// Audio is queued on Rust side and handed over to the recognizer only when it reads it
+/// (it is backed by *PullAudioInputStream*), so *write* awaits until recognizer catches
+/// up instead of growing native buffer without limits when the service slows down.
+#[derive(Debug)]
+pub struct BoundedPushAudioInputStream {
+    pub stream: PullAudioInputStream,
+    shared: Arc<Shared>,
+    bytes_per_second: u64,
+}
+
+impl AudioInputStream for BoundedPushAudioInputStream {
+    fn get_handle(&self) -> SPXAUDIOSTREAMHANDLE {
+        self.stream.get_handle()
+    }
+}
+
+impl BoundedPushAudioInputStream {
+    /// Creates stream of given (non compressed) *format* buffering at most *capacity* of audio.
+    pub fn create_push_stream(format: &AudioStreamFormat, capacity: Duration) -> Result<Self> {
+        let bytes_per_second = match (
+            format.get_samples_per_second(),
+            format.get_bits_per_sample(),
+            format.get_channels(),
+        ) {
+            (Some(samples_per_second), Some(bits_per_sample), Some(channels)) => {
+                u64::from(samples_per_second)
+                    * u64::from(bits_per_sample).div_ceil(8)
+                    * u64::from(channels)
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "BoundedPushAudioInputStream does not support compressed formats",
+                )
+                .into())
+            }
+        };
+        let capacity_bytes =
+            ((bytes_per_second as u128 * capacity.as_millis() / 1000) as usize).max(1);
+
+        let shared = Arc::new(Shared::new(capacity_bytes));
+        let mut stream = PullAudioInputStream::from_format(format)?;
+        stream.set_callbacks(
+            Box::new(QueueCallbacks {
+                shared: shared.clone(),
+            }),
+            false,
+        )?;
+        Ok(BoundedPushAudioInputStream {
+            stream,
+            shared,
+            bytes_per_second,
+        })
+    }
+
+    /// Writes whole *buffer*, awaiting until the recognizer reads
+    /// enough queued audio whenever the capacity is exhausted.
+    pub async fn write(&self, buffer: impl AsRef<[u8]>) -> Result<()> {
+        self.shared.write(buffer.as_ref()).await
+    }
+
+    /// Writes as much of *buffer* as fits into remaining capacity without waiting.
+    /// Returns number of bytes written.
+    pub fn try_write(&self, buffer: impl AsRef<[u8]>) -> Result<usize> {
+        self.shared.try_write(buffer.as_ref())
+    }
+
+    /// Signals end of the audio. Recognizer reads queued audio and then observes end of stream.
+    pub fn close_stream(&self) {
+        self.shared.close();
+    }
+
+    pub fn get_metrics(&self) -> PushAudioInputStreamMetrics {
+        let state = self.shared.lock();
+        let to_duration =
+            |bytes: u64| Duration::from_secs_f64(bytes as f64 / self.bytes_per_second as f64);
+        PushAudioInputStreamMetrics {
+            queued_bytes: state.queue.len() as u64,
+            queued_duration: to_duration(state.queue.len() as u64),
+            bytes_written: state.bytes_written,
+            bytes_consumed: state.bytes_consumed,
+            real_time_factor: state.first_read.map(|first_read| {
+                let elapsed = first_read.elapsed().as_secs_f64().max(f64::EPSILON);
+                to_duration(state.bytes_consumed).as_secs_f64() / elapsed
+            }),
+        }
+    }
+}
+
+impl Drop for BoundedPushAudioInputStream {
+    fn drop(&mut self) {
+        // do not leave recognizer blocked in read
+        self.shared.close();
+    }
+}
+
+/// Pull callbacks handing queued audio over to the recognizer.
+struct QueueCallbacks {
+    shared: Arc<Shared>,
+}
+
+impl PullAudioInputStreamCallbacks for QueueCallbacks {
+    fn read(&mut self, data_buffer: &mut [u8]) -> u32 {
+        let mut state = self.shared.lock();
+        while state.queue.is_empty() && !state.closed {
+            state = self
+                .shared
+                .data_available
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        let n = data_buffer.len().min(state.queue.len());
+        for (dst, src) in data_buffer.iter_mut().zip(state.queue.drain(..n)) {
+            *dst = src;
+        }
+        state.bytes_consumed += n as u64;
+        state.first_read.get_or_insert_with(Instant::now);
+        drop(state);
+        if n > 0 {
+            self.shared.space_available.notify_waiters();
+        }
+        n as u32
+    }
+
+    fn close(&mut self) {
+        trace!("BoundedPushAudioInputStream closed by recognizer");
+        self.shared.close();
+    }
+
+    fn get_property(&mut self, _id: i32) -> Result<String> {
+        Ok(String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn write_awaits_capacity() {
+        let shared = Arc::new(Shared::new(10));
+        let mut callbacks = QueueCallbacks {
+            shared: shared.clone(),
+        };
+        let reader = std::thread::spawn(move || {
+            let mut total = 0;
+            let mut buf = [0u8; 7];
+            loop {
+                let n = callbacks.read(&mut buf);
+                if n == 0 {
+                    return total;
+                }
+                total += n;
+            }
+        });
+
+        shared.write(&[1u8; 1000]).await.unwrap();
+        assert!(shared.lock().queue.len() <= 10);
+        shared.close();
+        assert_eq!(reader.join().unwrap(), 1000);
+        assert!(shared.try_write(&[1]).is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use crate::audio::{AudioStreamFormat, PullAudioInputStreamCallbacks};
+use crate::common::PropertyId;
+use crate::error::Result;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Resolution of *BufferTimestamp*, same as MPEG presentation timestamp.
+const TIMESTAMP_HZ: u64 = 90_000;
+
+/// Number of 100 ns ticks (unit of recognition offsets) per second.
+const TICKS_PER_SECOND: u64 = 10_000_000;
+
+/// BufferTimestamp is value of *PropertyId::DataBufferTimeStamp*, i.e. 64-bit
+/// time stamp with resolution of 90 kHz. Wall-clock time stamps are counted from UNIX epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BufferTimestamp(pub u64);
+
+impl BufferTimestamp {
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        BufferTimestamp::from_duration(since_epoch)
+    }
+
+    pub fn now() -> Self {
+        BufferTimestamp::from_system_time(SystemTime::now())
+    }
+
+    pub fn from_duration(duration: Duration) -> Self {
+        BufferTimestamp((duration.as_nanos() * u128::from(TIMESTAMP_HZ) / 1_000_000_000) as u64)
+    }
+
+    pub fn to_duration(self) -> Duration {
+        Duration::from_nanos((u128::from(self.0) * 1_000_000_000 / u128::from(TIMESTAMP_HZ)) as u64)
+    }
+
+    pub fn to_system_time(self) -> SystemTime {
+        UNIX_EPOCH + self.to_duration()
+    }
+
+    /// Returns time stamp shifted by *ticks* (100 ns units).
+    pub fn add_ticks(self, ticks: u64) -> Self {
+        BufferTimestamp(
+            self.0
+                + (u128::from(ticks) * u128::from(TIMESTAMP_HZ) / u128::from(TICKS_PER_SECOND))
+                    as u64,
+        )
+    }
+}
+
+impl fmt::Display for BufferTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Properties attached to audio buffer passed to the recognizer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BufferProperties {
+    pub timestamp: Option<BufferTimestamp>,
+    pub user_id: Option<String>,
+}
+
+impl BufferProperties {
+    pub fn new(timestamp: BufferTimestamp, user_id: impl Into<String>) -> Self {
+        BufferProperties {
+            timestamp: Some(timestamp),
+            user_id: Some(user_id.into()),
+        }
+    }
+
+    /// Returns value of *PropertyId::DataBufferTimeStamp* or *PropertyId::DataBufferUserID*.
+    pub fn get(&self, id: PropertyId) -> Option<String> {
+        match id {
+            PropertyId::DataBufferTimeStamp => self.timestamp.map(|ts| ts.to_string()),
+            PropertyId::DataBufferUserID => self.user_id.clone(),
+            _ => None,
+        }
+    }
+}
+
+/// BufferTimeline records properties of audio buffers by their position in the stream so
+/// that offsets of recognition results (in 100 ns ticks) can be mapped back to time stamps
+/// and user ids. Keep one clone in recognizer callbacks and feed audio through another,
+/// lookups see buffers added up to that moment.
+#[derive(Debug, Clone)]
+pub struct BufferTimeline {
+    bytes_per_second: u64,
+    // (offset in ticks, properties) of every buffer
+    buffers: Arc<Mutex<Vec<(u64, BufferProperties)>>>,
+}
+
+impl BufferTimeline {
+    pub fn new(bytes_per_second: u32) -> Self {
+        BufferTimeline {
+            bytes_per_second: u64::from(bytes_per_second.max(1)),
+            buffers: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    /// Creates timeline for given stream format, *None* for compressed formats.
+    pub fn from_format(format: &AudioStreamFormat) -> Option<Self> {
+        let bytes_per_second = format.get_samples_per_second()?
+            * u32::from(format.get_channels()?)
+            * u32::from(format.get_bits_per_sample()?).div_ceil(8);
+        Some(BufferTimeline::new(bytes_per_second))
+    }
+
+    /// Records properties of buffer starting at *offset_bytes* of the stream.
+    pub fn record(&self, offset_bytes: u64, properties: &BufferProperties) {
+        let ticks = (u128::from(offset_bytes) * u128::from(TICKS_PER_SECOND)
+            / u128::from(self.bytes_per_second)) as u64;
+        self.lock().push((ticks, properties.clone()));
+    }
+
+    /// Returns properties of the buffer containing *offset_ticks*
+    /// with time stamp adjusted to the exact offset.
+    pub fn properties_at(&self, offset_ticks: u64) -> Option<BufferProperties> {
+        let buffers = self.lock();
+        let index = buffers.partition_point(|(start, _)| *start <= offset_ticks);
+        let (start, properties) = buffers.get(index.checked_sub(1)?)?;
+        Some(BufferProperties {
+            timestamp: properties
+                .timestamp
+                .map(|ts| ts.add_ticks(offset_ticks - start)),
+            user_id: properties.user_id.clone(),
+        })
+    }
+
+    /// Returns wall-clock time of *offset_ticks* of the stream.
+    pub fn timestamp_at(&self, offset_ticks: u64) -> Option<BufferTimestamp> {
+        self.properties_at(offset_ticks)?.timestamp
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(u64, BufferProperties)>> {
+        // timeline stays usable from callbacks even if other thread panicked while holding the lock
+        self.buffers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns user id of the buffer containing *offset_ticks*.
+    pub fn user_id_at(&self, offset_ticks: u64) -> Option<String> {
+        self.properties_at(offset_ticks)?.user_id
+    }
+}
+
+/// Typed counterpart of *PullAudioInputStreamCallbacks*, every read
+/// returns properties (time stamp, user id) of the returned buffer.
+/// Wrap it into *BufferPropertiesCallbacks* to pass it to *PullAudioInputStream*.
+pub trait PullAudioInputStreamBufferCallbacks: Send {
+    /// Same as *PullAudioInputStreamCallbacks::read*, additionally
+    /// returns properties associated with populated data.
+    fn read(&mut self, data_buffer: &mut [u8]) -> (u32, BufferProperties);
+
+    /// Closes underlying resources of struct implementing this trait.
+    fn close(&mut self);
+}
+
+/// BufferPropertiesCallbacks adapts *PullAudioInputStreamBufferCallbacks* to
+/// *PullAudioInputStreamCallbacks* answering recognizer's *get_property* queries
+/// with properties of the last read buffer. Must be registered with *register_get_prop_cb*.
+pub struct BufferPropertiesCallbacks {
+    inner: Box<dyn PullAudioInputStreamBufferCallbacks>,
+    current: BufferProperties,
+    timeline: Option<BufferTimeline>,
+    offset_bytes: u64,
+}
+
+impl fmt::Debug for BufferPropertiesCallbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferPropertiesCallbacks")
+            .field("current", &self.current)
+            .field("offset_bytes", &self.offset_bytes)
+            .finish()
+    }
+}
+
+impl BufferPropertiesCallbacks {
+    /// When *timeline* is provided properties of every buffer are recorded into it.
+    pub fn new(
+        inner: Box<dyn PullAudioInputStreamBufferCallbacks>,
+        timeline: Option<BufferTimeline>,
+    ) -> Self {
+        BufferPropertiesCallbacks {
+            inner,
+            current: BufferProperties::default(),
+            timeline,
+            offset_bytes: 0,
+        }
+    }
+}
+
+impl PullAudioInputStreamCallbacks for BufferPropertiesCallbacks {
+    fn read(&mut self, data_buffer: &mut [u8]) -> u32 {
+        let (size, properties) = self.inner.read(data_buffer);
+        if size > 0 {
+            if let Some(timeline) = &self.timeline {
+                timeline.record(self.offset_bytes, &properties);
+            }
+            self.offset_bytes += u64::from(size);
+        }
+        self.current = properties;
+        size
+    }
+
+    fn close(&mut self) {
+        self.inner.close();
+    }
+
+    fn get_property(&mut self, id: i32) -> Result<String> {
+        let value = if id == PropertyId::DataBufferTimeStamp.to_i32() {
+            self.current.get(PropertyId::DataBufferTimeStamp)
+        } else if id == PropertyId::DataBufferUserID.to_i32() {
+            self.current.get(PropertyId::DataBufferUserID)
+        } else {
+            None
+        };
+        Ok(value.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeline_maps_offsets_to_timestamps() {
+        // 16 kHz, 16 bit, mono
+        let timeline = BufferTimeline::new(32000);
+        let start = BufferTimestamp::from_duration(Duration::from_secs(1_700_000_000));
+        timeline.record(0, &BufferProperties::new(start, "alice"));
+        timeline.record(
+            32000,
+            &BufferProperties::new(start.add_ticks(TICKS_PER_SECOND * 5), "bob"),
+        );
+
+        assert_eq!(timeline.timestamp_at(0), Some(start));
+        assert_eq!(
+            timeline.timestamp_at(TICKS_PER_SECOND / 2),
+            Some(BufferTimestamp(start.0 + 45_000))
+        );
+        assert_eq!(timeline.user_id_at(TICKS_PER_SECOND / 2).unwrap(), "alice");
+        assert_eq!(
+            timeline.timestamp_at(TICKS_PER_SECOND),
+            Some(start.add_ticks(TICKS_PER_SECOND * 5))
+        );
+        assert_eq!(timeline.user_id_at(TICKS_PER_SECOND * 2).unwrap(), "bob");
+        assert_eq!(
+            start.to_system_time(),
+            UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+        );
+    }
+}
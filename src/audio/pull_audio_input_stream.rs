@@ -1,4 +1,7 @@
-use crate::audio::{AudioInputStream, AudioStreamFormat};
+use crate::audio::{
+    AsyncReadCallbacks, AudioInputStream, AudioStreamFormat, BufferPropertiesCallbacks,
+    BufferTimeline, PullAudioInputStreamBufferCallbacks, ReadCallbacks,
+};
 use crate::error::{convert_err, Result};
 use crate::ffi::{
     audio_stream_create_pull_audio_input_stream, audio_stream_release,
@@ -9,8 +12,10 @@ use log::*;
 use std::convert::TryFrom;
 use std::ffi::CString;
 use std::fmt;
+use std::io::Read;
 use std::mem::MaybeUninit;
 use std::os::raw::{c_char, c_int, c_void};
+use tokio::io::AsyncRead;
 
 /// This trait that must be implemented by callback struct
 /// passed into  pull audio input stream during initialization.
@@ -38,7 +43,8 @@ pub trait PullAudioInputStreamCallbacks: Send {
     /// **ConversationTranscribingService_DataBufferTimeStamp (11001)**<br/>
     /// **ConversationTranscribingService_DataBufferUserId (11002)**<br/>
     /// For mor details see:
-    /// [PropertyId Enum definition](https://docs.microsoft.com/en-us/dotnet/api/microsoft.cognitiveservices.speech.propertyid?view=azure-dotnet).
+    /// [PropertyId Enum definition](https://docs.microsoft.com/en-us/dotnet/api/microsoft.cognitiveservices.speech.propertyid?view=azure-dotnet).<br/>
+    /// For typed alternative see *PullAudioInputStreamBufferCallbacks*.
     fn get_property(&mut self, id: i32) -> Result<String>;
 }
 
@@ -46,7 +52,9 @@ pub trait PullAudioInputStreamCallbacks: Send {
 /// Passing audio input is controlled by receiver.
 pub struct PullAudioInputStream {
     pub handle: SmartHandle<SPXAUDIOSTREAMHANDLE>,
-    callbacks: Option<Box<dyn PullAudioInputStreamCallbacks>>,
+    // double boxed so that pointer passed to native callbacks
+    // stays valid when PullAudioInputStream itself is moved
+    callbacks: Option<Box<Box<dyn PullAudioInputStreamCallbacks>>>,
 }
 
 impl fmt::Debug for PullAudioInputStream {
@@ -89,6 +97,40 @@ impl PullAudioInputStream {
         PullAudioInputStream::from_format(&default_format)
     }
 
+    /// Creates pull stream reading audio in given *format* from blocking *reader*.
+    pub fn from_reader<R>(format: &AudioStreamFormat, reader: R) -> Result<Self>
+    where
+        R: Read + Send + 'static,
+    {
+        let mut stream = PullAudioInputStream::from_format(format)?;
+        stream.set_callbacks(Box::new(ReadCallbacks::new(reader)), false)?;
+        Ok(stream)
+    }
+
+    /// Creates pull stream reading audio in given *format* from asynchronous *reader*.
+    /// Must be called from within tokio runtime, see *AsyncReadCallbacks*.
+    pub fn from_async_reader<R>(format: &AudioStreamFormat, reader: R) -> Result<Self>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut stream = PullAudioInputStream::from_format(format)?;
+        stream.set_callbacks(Box::new(AsyncReadCallbacks::new(reader)), false)?;
+        Ok(stream)
+    }
+
+    /// Registers typed callbacks attaching time stamp and user id to every read buffer.
+    /// When *timeline* is provided, result offsets can be mapped back to buffer properties.
+    pub fn set_buffer_callbacks(
+        &mut self,
+        callbacks: Box<dyn PullAudioInputStreamBufferCallbacks>,
+        timeline: Option<BufferTimeline>,
+    ) -> Result<()> {
+        self.set_callbacks(
+            Box::new(BufferPropertiesCallbacks::new(callbacks, timeline)),
+            true,
+        )
+    }
+
     /// Registers callbacks for speech recognizer. Callback for pull read
     /// and close are mandatory. Optionally (register_get_prop_cb = true)
     /// get property callback can be registered as well.
@@ -97,11 +139,13 @@ impl PullAudioInputStream {
         callbacks: Box<dyn PullAudioInputStreamCallbacks>,
         register_get_prop_cb: bool,
     ) -> Result<()> {
-        self.callbacks = Some(callbacks);
+        let callbacks = self.callbacks.insert(Box::new(callbacks));
+        let context =
+            &mut **callbacks as *mut Box<dyn PullAudioInputStreamCallbacks> as *mut c_void;
         unsafe {
             let mut ret = pull_audio_input_stream_set_callbacks(
                 self.handle.inner(),
-                self as *const _ as *mut c_void,
+                context,
                 Some(Self::cb_read),
                 Some(Self::cb_close),
             );
@@ -113,7 +157,7 @@ impl PullAudioInputStream {
             if register_get_prop_cb {
                 ret = pull_audio_input_stream_set_getproperty_callback(
                     self.handle.inner(),
-                    self as *const _ as *mut c_void,
+                    context,
                     Some(Self::cb_get_property),
                 );
                 convert_err(
@@ -129,10 +173,7 @@ impl PullAudioInputStream {
     #[allow(non_snake_case)]
     #[allow(unused_variables)]
     unsafe extern "C" fn cb_read(pvContext: *mut c_void, buffer: *mut u8, size: u32) -> c_int {
-        let pullstream = &mut *(pvContext as *mut PullAudioInputStream);
-        let callbacks: &mut Option<Box<(dyn PullAudioInputStreamCallbacks)>> =
-            &mut pullstream.callbacks;
-        let callbacks = callbacks.as_mut().unwrap();
+        let callbacks = &mut *(pvContext as *mut Box<dyn PullAudioInputStreamCallbacks>);
 
         let converted_size = usize::try_from(size);
         if let Err(conv_err) = converted_size {
@@ -151,10 +192,7 @@ impl PullAudioInputStream {
     #[allow(non_snake_case)]
     #[allow(unused_variables)]
     unsafe extern "C" fn cb_close(pvContext: *mut c_void) {
-        let pullstream = &mut *(pvContext as *mut PullAudioInputStream);
-        let callbacks: &mut Option<Box<(dyn PullAudioInputStreamCallbacks)>> =
-            &mut pullstream.callbacks;
-        let callbacks = callbacks.as_mut().unwrap();
+        let callbacks = &mut *(pvContext as *mut Box<dyn PullAudioInputStreamCallbacks>);
 
         callbacks.close();
     }
@@ -167,10 +205,7 @@ impl PullAudioInputStream {
         value: *mut u8,
         size: u32,
     ) {
-        let pullstream = &mut *(pvContext as *mut PullAudioInputStream);
-        let callbacks: &mut Option<Box<(dyn PullAudioInputStreamCallbacks)>> =
-            &mut pullstream.callbacks;
-        let callbacks = callbacks.as_mut().unwrap();
+        let callbacks = &mut *(pvContext as *mut Box<dyn PullAudioInputStreamCallbacks>);
 
         let converted_size = usize::try_from(size);
         if let Err(conv_err) = converted_size {
@@ -5,6 +5,7 @@ use crate::ffi::{
     pull_audio_output_stream_read, SmartHandle, SPXAUDIOSTREAMHANDLE,
 };
 use std::convert::TryFrom;
+use std::io;
 use std::mem::MaybeUninit;
 
 /// PullAudioOutputStream represents audio output stream with audio data pulled (read) by service calling Speech Synthetizer via *read* method.
@@ -56,4 +57,30 @@ impl PullAudioOutputStream {
             Ok(slice_buffer.to_vec())
         }
     }
+
+    /// Reads audio into caller provided *buffer*, returning number of bytes read.
+    /// Blocks until data are available, 0 signals end of the stream.
+    pub fn read_into(&self, buffer: &mut [u8]) -> Result<usize> {
+        let size = u32::try_from(buffer.len()).unwrap_or(u32::MAX);
+        let mut filled_size: u32 = 0;
+        unsafe {
+            let ret = pull_audio_output_stream_read(
+                self.handle.inner(),
+                buffer.as_mut_ptr(),
+                size,
+                &mut filled_size,
+            );
+            convert_err(ret, "PullAudioOutputStream.read_into error")?;
+        }
+        Ok(usize::try_from(filled_size)?)
+    }
+}
+
+impl io::Read for PullAudioOutputStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.read_into(buf).map_err(io::Error::other)
+    }
 }
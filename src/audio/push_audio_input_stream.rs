@@ -1,4 +1,6 @@
-use crate::audio::{AudioInputStream, AudioStreamFormat};
+use crate::audio::{
+    AudioInputStream, AudioRecorder, AudioStreamFormat, BufferProperties, BufferTimeline,
+};
 use crate::common::PropertyId;
 use crate::error::{convert_err, Result};
 use crate::ffi::{
@@ -16,6 +18,9 @@ use std::mem::MaybeUninit;
 #[derive(Debug)]
 pub struct PushAudioInputStream {
     pub handle: SmartHandle<SPXAUDIOSTREAMHANDLE>,
+    recorder: Option<AudioRecorder>,
+    timeline: Option<BufferTimeline>,
+    written_bytes: u64,
 }
 
 impl AudioInputStream for PushAudioInputStream {
@@ -45,6 +50,9 @@ impl PushAudioInputStream {
                     handle.assume_init(),
                     audio_stream_release,
                 ),
+                recorder: None,
+                timeline: None,
+                written_bytes: 0,
             })
         }
     }
@@ -70,6 +78,9 @@ impl PushAudioInputStream {
                     handle.assume_init(),
                     audio_stream_release,
                 ),
+                recorder: None,
+                timeline: None,
+                written_bytes: 0,
             })
         }
     }
@@ -81,16 +92,55 @@ impl PushAudioInputStream {
             let ptr = buf.as_ptr() as *mut u8;
             let ret = push_audio_input_stream_write(self.handle.inner(), ptr, buf.len() as u32);
             convert_err(ret, "PushAudioInputStream.write error")?;
+            if let Some(recorder) = &self.recorder {
+                recorder.write(buf);
+            }
+            self.written_bytes += buf.len() as u64;
             Ok(())
         }
     }
 
+    /// Writes audio buffer with associated time stamp and/or user id
+    /// (*PropertyId::DataBufferTimeStamp*, *PropertyId::DataBufferUserID*).
+    /// Properties are recorded into timeline set by *set_timeline*.
+    pub fn write_with_properties(
+        &mut self,
+        buffer: impl AsRef<[u8]>,
+        properties: &BufferProperties,
+    ) -> Result<()> {
+        if let Some(timestamp) = properties.timestamp {
+            self.set_property(PropertyId::DataBufferTimeStamp, timestamp.to_string())?;
+        }
+        if let Some(user_id) = &properties.user_id {
+            self.set_property(PropertyId::DataBufferUserID, user_id.clone())?;
+        }
+        if let Some(timeline) = &self.timeline {
+            timeline.record(self.written_bytes, properties);
+        }
+        self.write(buffer)
+    }
+
+    /// Sets timeline recording properties of buffers written by *write_with_properties*.
+    pub fn set_timeline(&mut self, timeline: BufferTimeline) {
+        self.timeline = Some(timeline);
+    }
+
     pub fn close_stream(&self) -> Result<()> {
         unsafe {
             let ret = push_audio_input_stream_close(self.handle.inner());
             convert_err(ret, "PushAudioInputStream.close_stream error")?;
-            Ok(())
         }
+        if let Some(recorder) = &self.recorder {
+            if let Err(err) = recorder.finish() {
+                error!("PushAudioInputStream.close_stream recorder error: {}", err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Mirrors all audio subsequently written into the stream into *recorder*.
+    pub fn set_recorder(&mut self, recorder: AudioRecorder) {
+        self.recorder = Some(recorder);
     }
 
     pub fn set_property_by_name(&mut self, name: String, value: String) -> Result<()> {
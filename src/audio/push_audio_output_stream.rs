@@ -1,4 +1,4 @@
-use crate::audio::AudioOutputStream;
+use crate::audio::{AsyncWriteCallbacks, AudioOutputStream, SenderCallbacks, WriteCallbacks};
 use crate::error::{convert_err, Result};
 use crate::ffi::{
     audio_stream_create_push_audio_output_stream, audio_stream_release,
@@ -7,8 +7,12 @@ use crate::ffi::{
 use log::*;
 use std::convert::TryFrom;
 use std::fmt;
+use std::io::{self, Write};
 use std::mem::MaybeUninit;
 use std::os::raw::{c_int, c_void};
+use tokio::io::AsyncWrite;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 /// This trait that must be implemented by callback struct
 /// passed into  push audio output stream during initialization.
@@ -33,7 +37,9 @@ pub trait PushAudioOutputStreamCallbacks: Send {
 /// Speech Synthetizer's caller is passivelly receiving already synthetized audio data via registered *write* callback.
 pub struct PushAudioOutputStream {
     pub handle: SmartHandle<SPXAUDIOSTREAMHANDLE>,
-    callbacks: Option<Box<dyn PushAudioOutputStreamCallbacks>>,
+    // double boxed so that pointer passed to native callbacks
+    // stays valid when PushAudioOutputStream itself is moved
+    callbacks: Option<Box<Box<dyn PushAudioOutputStreamCallbacks>>>,
 }
 
 impl fmt::Debug for PushAudioOutputStream {
@@ -69,16 +75,50 @@ impl PushAudioOutputStream {
         }
     }
 
+    /// Creates push stream writing synthesized audio into blocking *writer*.
+    pub fn from_writer<W>(writer: W) -> Result<Self>
+    where
+        W: Write + Send + 'static,
+    {
+        let mut stream = PushAudioOutputStream::create_push_stream()?;
+        stream.set_callbacks(Box::new(WriteCallbacks::new(writer)))?;
+        Ok(stream)
+    }
+
+    /// Creates push stream writing synthesized audio into asynchronous *writer*.
+    /// Returned join handle resolves with number of bytes written once
+    /// the stream is closed and writer flushed and shut down.
+    /// Must be called from within tokio runtime.
+    pub fn from_async_writer<W>(writer: W) -> Result<(Self, JoinHandle<io::Result<u64>>)>
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let (callbacks, completion) = AsyncWriteCallbacks::new(writer);
+        let mut stream = PushAudioOutputStream::create_push_stream()?;
+        stream.set_callbacks(Box::new(callbacks))?;
+        Ok((stream, completion))
+    }
+
+    /// Creates push stream sending synthesized audio chunks into *sender*.
+    /// Channel is closed (sender dropped) when the stream is closed.
+    pub fn from_sender(sender: mpsc::Sender<Vec<u8>>) -> Result<Self> {
+        let mut stream = PushAudioOutputStream::create_push_stream()?;
+        stream.set_callbacks(Box::new(SenderCallbacks::new(sender)))?;
+        Ok(stream)
+    }
+
     /// Registers callbacks for speech synthetizer.
     pub fn set_callbacks(
         &mut self,
         callbacks: Box<dyn PushAudioOutputStreamCallbacks>,
     ) -> Result<()> {
-        self.callbacks = Some(callbacks);
+        let callbacks = self.callbacks.insert(Box::new(callbacks));
+        let context =
+            &mut **callbacks as *mut Box<dyn PushAudioOutputStreamCallbacks> as *mut c_void;
         unsafe {
             let ret = push_audio_output_stream_set_callbacks(
                 self.handle.inner(),
-                self as *const _ as *mut c_void,
+                context,
                 Some(Self::cb_write),
                 Some(Self::cb_close),
             );
@@ -90,10 +130,7 @@ impl PushAudioOutputStream {
     #[allow(non_snake_case)]
     #[allow(unused_variables)]
     unsafe extern "C" fn cb_write(pvContext: *mut c_void, buffer: *mut u8, size: u32) -> c_int {
-        let pushstream = &mut *(pvContext as *mut PushAudioOutputStream);
-        let callbacks: &mut Option<Box<(dyn PushAudioOutputStreamCallbacks)>> =
-            &mut pushstream.callbacks;
-        let callbacks = callbacks.as_mut().unwrap();
+        let callbacks = &mut *(pvContext as *mut Box<dyn PushAudioOutputStreamCallbacks>);
 
         let converted_size = usize::try_from(size);
         if let Err(conv_err) = converted_size {
@@ -112,10 +149,7 @@ impl PushAudioOutputStream {
     #[allow(non_snake_case)]
     #[allow(unused_variables)]
     unsafe extern "C" fn cb_close(pvContext: *mut c_void) {
-        let pushstream = &mut *(pvContext as *mut PushAudioOutputStream);
-        let callbacks: &mut Option<Box<(dyn PushAudioOutputStreamCallbacks)>> =
-            &mut pushstream.callbacks;
-        let callbacks = callbacks.as_mut().unwrap();
+        let callbacks = &mut *(pvContext as *mut Box<dyn PushAudioOutputStreamCallbacks>);
 
         callbacks.close();
     }
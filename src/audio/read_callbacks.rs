@@ -0,0 +1,185 @@
+use crate::audio::PullAudioInputStreamCallbacks;
+use crate::error::Result;
+use log::*;
+use std::io::{self, Read};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Size of the chunks read from asynchronous source.
+const ASYNC_READ_CHUNK_SIZE: usize = 3200;
+
+/// Number of chunks buffered between asynchronous source and the recognizer.
+const ASYNC_READ_CHANNEL_CAPACITY: usize = 16;
+
+/// ReadCallbacks implements *PullAudioInputStreamCallbacks* for any blocking *Read*
+/// (file, socket, child process stdout...). Reads block until source
+/// provides data, EOF and read errors end the stream.
+#[derive(Debug)]
+pub struct ReadCallbacks<R> {
+    reader: Option<R>,
+}
+
+impl<R: Read + Send> ReadCallbacks<R> {
+    pub fn new(reader: R) -> Self {
+        ReadCallbacks {
+            reader: Some(reader),
+        }
+    }
+}
+
+impl<R: Read + Send> PullAudioInputStreamCallbacks for ReadCallbacks<R> {
+    fn read(&mut self, data_buffer: &mut [u8]) -> u32 {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return 0,
+        };
+        loop {
+            match reader.read(data_buffer) {
+                Ok(n) => return n as u32,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    error!("ReadCallbacks::read error, ending stream: {}", err);
+                    self.reader = None;
+                    return 0;
+                }
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        trace!("ReadCallbacks::close");
+        self.reader = None;
+    }
+
+    fn get_property(&mut self, _id: i32) -> Result<String> {
+        Ok(String::new())
+    }
+}
+
+/// AsyncReadCallbacks implements *PullAudioInputStreamCallbacks* for any tokio *AsyncRead*.
+/// Source is read by task spawned on tokio runtime and handed over via bounded channel,
+/// so the read callback (called on native SDK thread) blocks only on the channel
+/// and never needs to drive the runtime itself.
+#[derive(Debug)]
+pub struct AsyncReadCallbacks {
+    rx: Option<mpsc::Receiver<Vec<u8>>>,
+    task: JoinHandle<()>,
+    pending: Vec<u8>,
+    position: usize,
+}
+
+impl AsyncReadCallbacks {
+    /// Spawns reading task on current tokio runtime. Panics when called outside of runtime.
+    pub fn new<R>(reader: R) -> Self
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        Self::with_handle(reader, &Handle::current())
+    }
+
+    /// Spawns reading task on runtime identified by *handle*.
+    pub fn with_handle<R>(mut reader: R, handle: &Handle) -> Self
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(ASYNC_READ_CHANNEL_CAPACITY);
+        let task = handle.spawn(async move {
+            loop {
+                let mut buf = vec![0u8; ASYNC_READ_CHUNK_SIZE];
+                match reader.read(&mut buf).await {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        buf.truncate(n);
+                        if tx.send(buf).await.is_err() {
+                            trace!("AsyncReadCallbacks receiver closed");
+                            break;
+                        }
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => {
+                        error!("AsyncReadCallbacks read error, ending stream: {}", err);
+                        break;
+                    }
+                }
+            }
+        });
+        AsyncReadCallbacks {
+            rx: Some(rx),
+            task,
+            pending: vec![],
+            position: 0,
+        }
+    }
+}
+
+impl PullAudioInputStreamCallbacks for AsyncReadCallbacks {
+    fn read(&mut self, data_buffer: &mut [u8]) -> u32 {
+        if self.position == self.pending.len() {
+            let rx = match self.rx.as_mut() {
+                Some(rx) => rx,
+                None => return 0,
+            };
+            // called from native SDK thread, blocking here does not stall the runtime
+            match rx.blocking_recv() {
+                Some(chunk) => {
+                    self.pending = chunk;
+                    self.position = 0;
+                }
+                None => {
+                    self.rx = None;
+                    return 0;
+                }
+            }
+        }
+        let n = data_buffer.len().min(self.pending.len() - self.position);
+        data_buffer[..n].copy_from_slice(&self.pending[self.position..self.position + n]);
+        self.position += n;
+        n as u32
+    }
+
+    fn close(&mut self) {
+        trace!("AsyncReadCallbacks::close");
+        self.rx = None;
+        self.task.abort();
+    }
+
+    fn get_property(&mut self, _id: i32) -> Result<String> {
+        Ok(String::new())
+    }
+}
+
+impl Drop for AsyncReadCallbacks {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_callbacks_map_eof_to_zero() {
+        let mut callbacks = ReadCallbacks::new(&b"abcde"[..]);
+        let mut buf = [0u8; 4];
+        assert_eq!(callbacks.read(&mut buf), 4);
+        assert_eq!(callbacks.read(&mut buf), 1);
+        assert_eq!(callbacks.read(&mut buf), 0);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut callbacks = AsyncReadCallbacks::with_handle(&b"abcde"[..], runtime.handle());
+        let mut data = vec![];
+        loop {
+            let n = callbacks.read(&mut buf) as usize;
+            if n == 0 {
+                break;
+            }
+            data.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(data, b"abcde");
+        callbacks.close();
+        assert_eq!(callbacks.read(&mut buf), 0);
+    }
+}
@@ -0,0 +1,466 @@
+use crate::audio::{
+    AudioInputStream, PcmFormat, PullAudioInputStreamCallbacks, PushAudioInputStream, SampleFormat,
+};
+use crate::error::Result;
+use crate::ffi::SPXAUDIOSTREAMHANDLE;
+use log::*;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+/// Number of 100 ns ticks (unit of recognition offsets) per second.
+const TICKS_PER_SECOND: u64 = 10_000_000;
+
+/// Configuration of *VoiceActivityGate*.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoiceActivityGateConfig {
+    /// Length of the frame energy is evaluated on.
+    pub frame_ms: u32,
+    /// Frames with RMS level above this threshold (in dBFS) are considered voice.
+    pub threshold_dbfs: f32,
+    /// How long audio keeps being forwarded after the last voiced frame.
+    pub hangover_ms: u32,
+    /// How much audio preceding voice onset is forwarded, so that word beginnings are not cut off.
+    pub pre_roll_ms: u32,
+    /// Silence injected in place of every dropped silent segment so that
+    /// the recognizer still observes a pause (and ends the phrase).
+    pub padding_ms: u32,
+}
+
+impl Default for VoiceActivityGateConfig {
+    fn default() -> Self {
+        VoiceActivityGateConfig {
+            frame_ms: 20,
+            threshold_dbfs: -45.0,
+            hangover_ms: 500,
+            pre_roll_ms: 300,
+            padding_ms: 200,
+        }
+    }
+}
+
+/// Statistics of the gate, in bytes of input audio.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VoiceActivityGateStats {
+    pub received_bytes: u64,
+    pub forwarded_bytes: u64,
+    pub dropped_bytes: u64,
+    pub padding_bytes: u64,
+}
+
+/// OffsetMap translates offsets reported by the recognizer (in 100 ns ticks, relative to
+/// gated audio) into offsets in the original audio. *VoiceActivityGate::offset_map* returns
+/// a handle to the map which the gate keeps extending as it forwards audio.
+#[derive(Debug, Clone, Default)]
+pub struct OffsetMap {
+    // (gated offset, original offset) at the start of every forwarded segment
+    segments: Arc<Mutex<Vec<(u64, u64)>>>,
+}
+
+impl OffsetMap {
+    fn push(&self, gated: u64, original: u64) {
+        self.lock().push((gated, original));
+    }
+
+    /// Returns offset in original audio corresponding to *gated_ticks*.
+    pub fn to_original(&self, gated_ticks: u64) -> u64 {
+        let segments = self.lock();
+        let index = segments.partition_point(|(gated, _)| *gated <= gated_ticks);
+        match index {
+            0 => gated_ticks,
+            index => {
+                let (gated, original) = segments[index - 1];
+                original + (gated_ticks - gated)
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(u64, u64)>> {
+        // map stays usable from callbacks even if other thread panicked while holding the lock
+        self.segments
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// VoiceActivityGate detects silence by frame energy and drops long silent
+/// segments so that they are not sent (and billed) by the service. Every dropped
+/// segment is replaced by short padding of silence and recorded in *OffsetMap*
+/// so that true timestamps can be restored. Supports 16 bit PCM only.
+#[derive(Debug)]
+pub struct VoiceActivityGate {
+    config: VoiceActivityGateConfig,
+    frame_len: usize,
+    bytes_per_frame: u64,
+    bytes_per_second: u64,
+    threshold: f64,
+    /// Incomplete frame left over from previous input.
+    partial: Vec<u8>,
+    /// Silent frames kept for pre-roll while gate is closed.
+    pre_roll: VecDeque<Vec<u8>>,
+    pre_roll_frames: usize,
+    hangover_frames: usize,
+    /// Number of silent frames since last voiced frame, *None* while gate is closed.
+    open_silence: Option<usize>,
+    /// Bytes of silence dropped since the gate closed.
+    dropped_since_close: u64,
+    original_bytes: u64,
+    gated_bytes: u64,
+    stats: VoiceActivityGateStats,
+    offsets: OffsetMap,
+}
+
+impl VoiceActivityGate {
+    pub fn new(format: PcmFormat, config: VoiceActivityGateConfig) -> Result<Self> {
+        if format.sample_format != SampleFormat::I16 || format.samples_per_second == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "VoiceActivityGate supports only 16 bit PCM",
+            )
+            .into());
+        }
+        let bytes_per_frame = format.bytes_per_frame() as u64;
+        let frame_len =
+            (format.samples_per_second as usize * config.frame_ms.max(1) as usize / 1000).max(1)
+                * format.bytes_per_frame();
+        let frames = |ms: u32| (ms as usize).div_ceil(config.frame_ms.max(1) as usize);
+        Ok(VoiceActivityGate {
+            config,
+            frame_len,
+            bytes_per_frame,
+            // kept per second, per millisecond value is fractional e.g. for 44.1 kHz
+            bytes_per_second: u64::from(format.samples_per_second) * bytes_per_frame,
+            threshold: 32768.0 * 10f64.powf(f64::from(config.threshold_dbfs) / 20.0),
+            partial: vec![],
+            pre_roll: VecDeque::new(),
+            pre_roll_frames: frames(config.pre_roll_ms),
+            hangover_frames: frames(config.hangover_ms),
+            // start closed, leading silence is dropped as well
+            open_silence: None,
+            dropped_since_close: 0,
+            original_bytes: 0,
+            gated_bytes: 0,
+            stats: VoiceActivityGateStats::default(),
+            offsets: OffsetMap::default(),
+        })
+    }
+
+    pub fn stats(&self) -> VoiceActivityGateStats {
+        self.stats
+    }
+
+    pub fn offset_map(&self) -> OffsetMap {
+        self.offsets.clone()
+    }
+
+    /// Processes next piece of audio, returning audio which should be forwarded to the recognizer.
+    pub fn process(&mut self, input: &[u8]) -> Vec<u8> {
+        self.stats.received_bytes += input.len() as u64;
+        self.partial.extend_from_slice(input);
+        let mut out = vec![];
+        let whole = self.partial.len() / self.frame_len * self.frame_len;
+        let frames: Vec<Vec<u8>> = self.partial[..whole]
+            .chunks_exact(self.frame_len)
+            .map(|frame| frame.to_vec())
+            .collect();
+        self.partial.drain(..whole);
+        for frame in frames {
+            self.process_frame(frame, &mut out);
+        }
+        out
+    }
+
+    /// Returns trailing incomplete frame once the input has ended.
+    pub fn flush(&mut self) -> Vec<u8> {
+        let partial = std::mem::take(&mut self.partial);
+        let pre_roll_len: u64 = self.pre_roll.drain(..).map(|f| f.len() as u64).sum();
+        self.stats.dropped_bytes += pre_roll_len;
+        let mut out = vec![];
+        if !partial.is_empty() && self.open_silence.is_some() {
+            self.forward(&partial, &mut out);
+        } else {
+            self.drop_bytes(partial.len() as u64);
+        }
+        out
+    }
+
+    fn process_frame(&mut self, frame: Vec<u8>, out: &mut Vec<u8>) {
+        let voiced = self.rms(&frame) >= self.threshold;
+        match (self.open_silence, voiced) {
+            (_, true) => {
+                if self.open_silence.is_none() {
+                    self.open(out);
+                }
+                self.open_silence = Some(0);
+                self.forward(&frame, out);
+            }
+            (Some(silence), false) if silence < self.hangover_frames => {
+                self.open_silence = Some(silence + 1);
+                self.forward(&frame, out);
+            }
+            (Some(_), false) => {
+                trace!("VoiceActivityGate closing");
+                self.open_silence = None;
+                self.dropped_since_close = 0;
+                self.buffer_silence(frame);
+            }
+            (None, false) => self.buffer_silence(frame),
+        }
+    }
+
+    /// Opens the gate: injects padding for dropped silence, records
+    /// offset mapping and forwards pre-roll audio.
+    fn open(&mut self, out: &mut Vec<u8>) {
+        trace!(
+            "VoiceActivityGate opening after {} dropped bytes",
+            self.dropped_since_close
+        );
+        let pre_roll_len: u64 = self.pre_roll.iter().map(|f| f.len() as u64).sum();
+        if self.dropped_since_close > 0 {
+            let padding = (self.bytes_per_second * u64::from(self.config.padding_ms) / 1000)
+                .min(self.dropped_since_close);
+            let padding = (padding / self.bytes_per_frame * self.bytes_per_frame) as usize;
+            out.extend(std::iter::repeat_n(0u8, padding));
+            self.gated_bytes += padding as u64;
+            self.stats.padding_bytes += padding as u64;
+        }
+        self.offsets.push(
+            self.to_ticks(self.gated_bytes),
+            self.to_ticks(self.original_bytes - pre_roll_len),
+        );
+        self.dropped_since_close = 0;
+        // pre-roll frames were counted as received only
+        self.original_bytes -= pre_roll_len;
+        for frame in std::mem::take(&mut self.pre_roll) {
+            self.forward(&frame, out);
+        }
+    }
+
+    fn buffer_silence(&mut self, frame: Vec<u8>) {
+        self.original_bytes += frame.len() as u64;
+        self.pre_roll.push_back(frame);
+        while self.pre_roll.len() > self.pre_roll_frames {
+            if let Some(dropped) = self.pre_roll.pop_front() {
+                self.dropped_since_close += dropped.len() as u64;
+                self.stats.dropped_bytes += dropped.len() as u64;
+            }
+        }
+    }
+
+    fn drop_bytes(&mut self, len: u64) {
+        self.original_bytes += len;
+        self.dropped_since_close += len;
+        self.stats.dropped_bytes += len;
+    }
+
+    fn forward(&mut self, data: &[u8], out: &mut Vec<u8>) {
+        out.extend_from_slice(data);
+        self.original_bytes += data.len() as u64;
+        self.gated_bytes += data.len() as u64;
+        self.stats.forwarded_bytes += data.len() as u64;
+    }
+
+    fn rms(&self, frame: &[u8]) -> f64 {
+        let samples = frame.len() / 2;
+        let sum: f64 = frame
+            .chunks_exact(2)
+            .map(|s| f64::from(i16::from_le_bytes([s[0], s[1]])).powi(2))
+            .sum();
+        (sum / samples.max(1) as f64).sqrt()
+    }
+
+    fn to_ticks(&self, bytes: u64) -> u64 {
+        bytes * TICKS_PER_SECOND / self.bytes_per_second.max(1)
+    }
+}
+
+/// GatedPushAudioInputStream wraps *PushAudioInputStream* and forwards
+/// only audio passing through *VoiceActivityGate*.
+#[derive(Debug)]
+pub struct GatedPushAudioInputStream {
+    pub stream: PushAudioInputStream,
+    gate: VoiceActivityGate,
+}
+
+impl AudioInputStream for GatedPushAudioInputStream {
+    fn get_handle(&self) -> SPXAUDIOSTREAMHANDLE {
+        self.stream.get_handle()
+    }
+}
+
+impl GatedPushAudioInputStream {
+    /// Creates push stream in *format* gated by voice activity.
+    pub fn create_push_stream(format: PcmFormat, config: VoiceActivityGateConfig) -> Result<Self> {
+        let gate = VoiceActivityGate::new(format, config)?;
+        let stream =
+            PushAudioInputStream::create_push_stream_from_format(format.to_audio_stream_format()?)?;
+        Ok(GatedPushAudioInputStream { stream, gate })
+    }
+
+    pub fn write(&mut self, buffer: impl AsRef<[u8]>) -> Result<()> {
+        let gated = self.gate.process(buffer.as_ref());
+        if !gated.is_empty() {
+            self.stream.write(gated)?;
+        }
+        Ok(())
+    }
+
+    pub fn close_stream(&mut self) -> Result<()> {
+        let gated = self.gate.flush();
+        if !gated.is_empty() {
+            self.stream.write(gated)?;
+        }
+        self.stream.close_stream()
+    }
+
+    pub fn stats(&self) -> VoiceActivityGateStats {
+        self.gate.stats()
+    }
+
+    pub fn offset_map(&self) -> OffsetMap {
+        self.gate.offset_map()
+    }
+}
+
+/// GatedCallbacks wraps *PullAudioInputStreamCallbacks* and returns to the
+/// recognizer only audio passing through *VoiceActivityGate*. Read blocks
+/// while the source produces silence only.
+pub struct GatedCallbacks {
+    inner: Box<dyn PullAudioInputStreamCallbacks>,
+    gate: VoiceActivityGate,
+    buffer: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl fmt::Debug for GatedCallbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GatedCallbacks")
+            .field("gate", &self.gate)
+            .finish()
+    }
+}
+
+impl GatedCallbacks {
+    pub fn new(
+        inner: Box<dyn PullAudioInputStreamCallbacks>,
+        format: PcmFormat,
+        config: VoiceActivityGateConfig,
+    ) -> Result<Self> {
+        Ok(GatedCallbacks {
+            inner,
+            gate: VoiceActivityGate::new(format, config)?,
+            buffer: vec![],
+            position: 0,
+            finished: false,
+        })
+    }
+
+    pub fn offset_map(&self) -> OffsetMap {
+        self.gate.offset_map()
+    }
+}
+
+impl PullAudioInputStreamCallbacks for GatedCallbacks {
+    fn read(&mut self, data_buffer: &mut [u8]) -> u32 {
+        while self.position == self.buffer.len() {
+            if self.finished || data_buffer.is_empty() {
+                return 0;
+            }
+            let mut input = vec![0u8; data_buffer.len()];
+            let n = self.inner.read(&mut input) as usize;
+            self.buffer = if n == 0 {
+                self.finished = true;
+                self.gate.flush()
+            } else {
+                self.gate.process(&input[..n])
+            };
+            self.position = 0;
+        }
+        let n = data_buffer.len().min(self.buffer.len() - self.position);
+        data_buffer[..n].copy_from_slice(&self.buffer[self.position..self.position + n]);
+        self.position += n;
+        n as u32
+    }
+
+    fn close(&mut self) {
+        self.inner.close();
+    }
+
+    fn get_property(&mut self, id: i32) -> Result<String> {
+        self.inner.get_property(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICKS_PER_MS: u64 = TICKS_PER_SECOND / 1000;
+
+    fn frames(value: i16, ms: usize) -> Vec<u8> {
+        // 16 kHz mono, 32 bytes per ms
+        value.to_le_bytes().repeat(16 * ms)
+    }
+
+    #[test]
+    fn maps_offsets_at_fractional_byte_rate() {
+        // 44.1 kHz mono carries 88.2 bytes per ms
+        let format = PcmFormat::new(44100, 1, SampleFormat::I16);
+        let frames = |value: i16, ms: usize| value.to_le_bytes().repeat(44100 * ms / 1000);
+        let config = VoiceActivityGateConfig {
+            pre_roll_ms: 100,
+            padding_ms: 200,
+            ..Default::default()
+        };
+        let mut gate = VoiceActivityGate::new(format, config).unwrap();
+        let mut out = vec![];
+        out.extend(gate.process(&frames(0, 60_000)));
+        out.extend(gate.process(&frames(10000, 500)));
+        out.extend(gate.flush());
+        assert_eq!(out.len() % 2, 0);
+        // pre-roll starts at 59.9 s of original audio, 200 ms into gated audio
+        assert_eq!(
+            gate.offset_map().to_original(200 * TICKS_PER_MS),
+            59_900 * TICKS_PER_MS
+        );
+    }
+
+    #[test]
+    fn drops_silence_and_maps_offsets() {
+        let config = VoiceActivityGateConfig {
+            frame_ms: 20,
+            threshold_dbfs: -40.0,
+            hangover_ms: 100,
+            pre_roll_ms: 100,
+            padding_ms: 200,
+        };
+        let mut gate = VoiceActivityGate::new(PcmFormat::default_input(), config).unwrap();
+        let mut out = vec![];
+        out.extend(gate.process(&frames(0, 2000)));
+        assert!(out.is_empty());
+        out.extend(gate.process(&frames(10000, 500)));
+        out.extend(gate.process(&frames(0, 3000)));
+        out.extend(gate.process(&frames(10000, 500)));
+        out.extend(gate.flush());
+
+        let stats = gate.stats();
+        assert_eq!(stats.received_bytes, 32 * 6000);
+        assert_eq!(
+            stats.forwarded_bytes + stats.dropped_bytes,
+            stats.received_bytes
+        );
+        // padding 200 + pre-roll 100 + voice 500 + hangover 100, then padding 200 + pre-roll 100 + voice 500
+        assert_eq!(out.len(), 32 * 1700);
+
+        let offsets = gate.offset_map();
+        // first pre-roll starts at 1900 ms of original audio, 200 ms into gated audio
+        assert_eq!(offsets.to_original(200 * TICKS_PER_MS), 1900 * TICKS_PER_MS);
+        // second voice onset at 5500 ms original, gated 200+100+500+100+200+100 = 1200 ms
+        assert_eq!(
+            offsets.to_original(1200 * TICKS_PER_MS),
+            5500 * TICKS_PER_MS
+        );
+    }
+}
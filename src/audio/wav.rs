@@ -0,0 +1,436 @@
+use crate::audio::{AudioStreamFormat, PushAudioInputStream, WaveFormat};
+use crate::error::Result;
+use log::*;
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+/// Default number of bytes written into push stream per write.
+pub const DEFAULT_WAV_CHUNK_SIZE: usize = 3200;
+
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// Largest accepted *fmt* chunk, WAVE_FORMAT_EXTENSIBLE with its cbSize tail takes 40 bytes.
+const MAX_FMT_CHUNK_LEN: u32 = 64;
+
+/// Parsed *fmt* chunk of the WAV file together with length of the *data* chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WavHeader {
+    pub wave_format: WaveFormat,
+    pub channels: u16,
+    pub samples_per_second: u32,
+    pub avg_bytes_per_second: u32,
+    pub block_align: u16,
+    pub bits_per_sample: u16,
+    /// Speaker positions, present only in *WAVE_FORMAT_EXTENSIBLE* headers.
+    pub channel_mask: Option<u32>,
+    /// Length of sample data in bytes. *None* when the header does not
+    /// specify it (streamed WAV), in which case data are read until EOF.
+    pub data_len: Option<u32>,
+}
+
+impl WavHeader {
+    pub fn new(
+        wave_format: WaveFormat,
+        samples_per_second: u32,
+        bits_per_sample: u16,
+        channels: u16,
+    ) -> Self {
+        let block_align = channels * bits_per_sample.div_ceil(8);
+        WavHeader {
+            wave_format,
+            channels,
+            samples_per_second,
+            avg_bytes_per_second: samples_per_second * u32::from(block_align),
+            block_align,
+            bits_per_sample,
+            channel_mask: None,
+            data_len: None,
+        }
+    }
+
+    /// Creates header describing samples in given format, *None* for compressed formats.
+    pub fn from_audio_stream_format(format: &AudioStreamFormat) -> Option<Self> {
+        Some(WavHeader::new(
+            format.get_wave_format_tag()?,
+            format.get_samples_per_second()?,
+            u16::from(format.get_bits_per_sample()?),
+            u16::from(format.get_channels()?),
+        ))
+    }
+
+    /// Creates *AudioStreamFormat* describing the sample data.
+    pub fn to_audio_stream_format(&self) -> Result<AudioStreamFormat> {
+        AudioStreamFormat::get_wave_format(
+            self.samples_per_second,
+            u8::try_from(self.bits_per_sample)?,
+            u8::try_from(self.channels)?,
+            self.wave_format,
+        )
+    }
+}
+
+/// WavReader parses RIFF header from underlying reader (e.g. HTTP body or object
+/// storage blob) and then yields sample data of the *data* chunk via *Read*,
+/// so that it can be pumped into *PushAudioInputStream* of matching format.
+#[derive(Debug)]
+pub struct WavReader<R> {
+    header: WavHeader,
+    reader: R,
+    remaining: Option<u64>,
+}
+
+impl<R: Read> WavReader<R> {
+    /// Reads RIFF header up to the beginning of the *data* chunk.
+    /// Chunks other than *fmt* and *data* (e.g. *LIST*, *fact*) are skipped.
+    /// Data are read until EOF only for streamed WAV, i.e. when *data* length
+    /// is 0xFFFFFFFF or when both RIFF and *data* lengths are left unset (0).
+    pub fn new(mut reader: R) -> Result<Self> {
+        let riff = read_array::<_, 12>(&mut reader)?;
+        if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+            return Err(invalid_data("missing RIFF/WAVE signature").into());
+        }
+        let riff_len = u32::from_le_bytes([riff[4], riff[5], riff[6], riff[7]]);
+        let streamed = riff_len == 0 || riff_len == u32::MAX;
+
+        let mut fmt: Option<WavHeader> = None;
+        loop {
+            let chunk = read_array::<_, 8>(&mut reader)?;
+            let chunk_id = [chunk[0], chunk[1], chunk[2], chunk[3]];
+            let chunk_len = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+            match &chunk_id {
+                b"fmt " => {
+                    if chunk_len > MAX_FMT_CHUNK_LEN {
+                        return Err(invalid_data(&format!(
+                            "fmt chunk too long ({} bytes)",
+                            chunk_len
+                        ))
+                        .into());
+                    }
+                    let mut body = vec![0u8; chunk_len as usize];
+                    reader.read_exact(&mut body)?;
+                    skip(&mut reader, u64::from(chunk_len % 2))?;
+                    fmt = Some(parse_fmt(&body)?);
+                }
+                b"data" => {
+                    let mut header =
+                        fmt.ok_or_else(|| invalid_data("data chunk precedes fmt chunk"))?;
+                    header.data_len = match chunk_len {
+                        u32::MAX => None,
+                        0 if streamed => None,
+                        len => Some(len),
+                    };
+                    trace!("WavReader::new header {:?}", header);
+                    return Ok(WavReader {
+                        remaining: header.data_len.map(u64::from),
+                        header,
+                        reader,
+                    });
+                }
+                _ => {
+                    trace!(
+                        "WavReader::new skipping chunk {}",
+                        String::from_utf8_lossy(&chunk_id)
+                    );
+                    skip(&mut reader, u64::from(chunk_len) + u64::from(chunk_len % 2))?;
+                }
+            }
+        }
+    }
+
+    pub fn header(&self) -> &WavHeader {
+        &self.header
+    }
+
+    /// Creates *AudioStreamFormat* describing the sample data.
+    pub fn audio_stream_format(&self) -> Result<AudioStreamFormat> {
+        self.header.to_audio_stream_format()
+    }
+
+    /// Creates push stream with format matching the WAV header.
+    pub fn create_push_stream(&self) -> Result<PushAudioInputStream> {
+        PushAudioInputStream::create_push_stream_from_format(self.audio_stream_format()?)
+    }
+
+    /// Writes remaining sample data into *stream* in chunks of *chunk_size*
+    /// bytes and closes the stream afterwards. Returns number of bytes written.
+    pub fn pump(&mut self, stream: &mut PushAudioInputStream, chunk_size: usize) -> Result<u64> {
+        let mut buf = vec![0u8; chunk_size.max(1)];
+        let mut total = 0;
+        loop {
+            let n = self.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            stream.write(&buf[..n])?;
+            total += n as u64;
+        }
+        stream.close_stream()?;
+        Ok(total)
+    }
+
+    /// Creates push stream matching the WAV header, pumps whole
+    /// sample data into it and closes it.
+    pub fn into_push_stream(mut self, chunk_size: usize) -> Result<PushAudioInputStream> {
+        let mut stream = self.create_push_stream()?;
+        self.pump(&mut stream, chunk_size)?;
+        Ok(stream)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Read for WavReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let max = match self.remaining {
+            Some(remaining) => buf
+                .len()
+                .min(usize::try_from(remaining).unwrap_or(usize::MAX)),
+            None => buf.len(),
+        };
+        if max == 0 {
+            return Ok(0);
+        }
+        let n = self.reader.read(&mut buf[..max])?;
+        if let Some(remaining) = self.remaining.as_mut() {
+            if n == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "WAV data chunk truncated",
+                ));
+            }
+            *remaining -= n as u64;
+        }
+        Ok(n)
+    }
+}
+
+/// WavWriter writes sample data into WAV file. Lengths in RIFF header
+/// are patched by *finalize*, which must be called once all samples are written.
+#[derive(Debug)]
+pub struct WavWriter<W: Write + Seek> {
+    writer: W,
+    data_len: u64,
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    /// Writes RIFF header (with zero lengths) into *writer*.
+    pub fn new(mut writer: W, header: &WavHeader) -> Result<Self> {
+        let mut riff = Vec::with_capacity(44);
+        riff.extend_from_slice(b"RIFF");
+        riff.extend_from_slice(&0u32.to_le_bytes());
+        riff.extend_from_slice(b"WAVEfmt ");
+        riff.extend_from_slice(&16u32.to_le_bytes());
+        riff.extend_from_slice(&u16::from(header.wave_format).to_le_bytes());
+        riff.extend_from_slice(&header.channels.to_le_bytes());
+        riff.extend_from_slice(&header.samples_per_second.to_le_bytes());
+        riff.extend_from_slice(&header.avg_bytes_per_second.to_le_bytes());
+        riff.extend_from_slice(&header.block_align.to_le_bytes());
+        riff.extend_from_slice(&header.bits_per_sample.to_le_bytes());
+        riff.extend_from_slice(b"data");
+        riff.extend_from_slice(&0u32.to_le_bytes());
+        writer.write_all(&riff)?;
+        Ok(WavWriter {
+            writer,
+            data_len: 0,
+        })
+    }
+
+    pub fn write_samples(&mut self, data: &[u8]) -> Result<()> {
+        self.writer.write_all(data)?;
+        self.data_len += data.len() as u64;
+        Ok(())
+    }
+
+    /// Number of sample bytes written so far.
+    pub fn data_len(&self) -> u64 {
+        self.data_len
+    }
+
+    /// Patches lengths in RIFF header and returns underlying writer.
+    pub fn finalize(mut self) -> Result<W> {
+        let data_len = u32::try_from(self.data_len).unwrap_or(u32::MAX);
+        if data_len % 2 == 1 {
+            self.writer.write_all(&[0])?;
+        }
+        let riff_len = 36u32.saturating_add(data_len).saturating_add(data_len % 2);
+        self.writer.seek(SeekFrom::Start(4))?;
+        self.writer.write_all(&riff_len.to_le_bytes())?;
+        self.writer.seek(SeekFrom::Start(40))?;
+        self.writer.write_all(&data_len.to_le_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+fn parse_fmt(body: &[u8]) -> Result<WavHeader> {
+    if body.len() < 16 {
+        return Err(invalid_data("fmt chunk too short").into());
+    }
+    let u16_at = |pos: usize| u16::from_le_bytes([body[pos], body[pos + 1]]);
+    let u32_at =
+        |pos: usize| u32::from_le_bytes([body[pos], body[pos + 1], body[pos + 2], body[pos + 3]]);
+
+    let mut format_tag = u16_at(0);
+    let mut channel_mask = None;
+    if format_tag == WAVE_FORMAT_EXTENSIBLE {
+        // cbSize, wValidBitsPerSample, dwChannelMask and SubFormat GUID
+        // whose first two bytes hold the actual format tag
+        if body.len() < 40 {
+            return Err(invalid_data("extensible fmt chunk too short").into());
+        }
+        channel_mask = Some(u32_at(20));
+        format_tag = u16_at(24);
+    }
+    let wave_format = WaveFormat::from_format_tag(format_tag)
+        .ok_or_else(|| invalid_data(&format!("unsupported WAV format tag {:#06x}", format_tag)))?;
+
+    Ok(WavHeader {
+        wave_format,
+        channels: u16_at(2),
+        samples_per_second: u32_at(4),
+        avg_bytes_per_second: u32_at(8),
+        block_align: u16_at(12),
+        bits_per_sample: u16_at(14),
+        channel_mask,
+        data_len: None,
+    })
+}
+
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn skip<R: Read>(reader: &mut R, len: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(len), &mut io::sink())?;
+    if skipped < len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "WAV chunk truncated",
+        ));
+    }
+    Ok(())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut chunk = id.to_vec();
+        chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(body);
+        if body.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    }
+
+    fn riff(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let body: Vec<u8> = chunks.concat();
+        let mut riff = b"RIFF".to_vec();
+        riff.extend_from_slice(&(body.len() as u32 + 4).to_le_bytes());
+        riff.extend_from_slice(b"WAVE");
+        riff.extend_from_slice(&body);
+        riff
+    }
+
+    fn fmt(tag: u16, channels: u16, rate: u32, bits: u16) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut fmt = vec![];
+        fmt.extend_from_slice(&tag.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&rate.to_le_bytes());
+        fmt.extend_from_slice(&(rate * u32::from(block_align)).to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+        fmt
+    }
+
+    #[test]
+    fn parses_pcm_with_list_chunk() {
+        let wav = riff(&[
+            chunk(b"LIST", b"INFOISFT\x03\x00\x00\x00abc"),
+            chunk(b"fmt ", &fmt(1, 2, 16000, 16)),
+            chunk(b"data", &[1, 2, 3, 4, 5, 6, 7, 8]),
+            chunk(b"LIST", b"trailing"),
+        ]);
+        let mut reader = WavReader::new(&wav[..]).unwrap();
+        assert_eq!(reader.header().wave_format, WaveFormat::Pcm);
+        assert_eq!(reader.header().channels, 2);
+        assert_eq!(reader.header().data_len, Some(8));
+        let mut data = vec![];
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn parses_extensible_mulaw() {
+        let mut body = fmt(WAVE_FORMAT_EXTENSIBLE, 1, 8000, 8);
+        body.extend_from_slice(&22u16.to_le_bytes());
+        body.extend_from_slice(&8u16.to_le_bytes());
+        body.extend_from_slice(&4u32.to_le_bytes());
+        body.extend_from_slice(&7u16.to_le_bytes());
+        body.extend_from_slice(&[0u8; 14]);
+        let wav = riff(&[chunk(b"fmt ", &body), chunk(b"data", &[0xff; 3])]);
+        let reader = WavReader::new(&wav[..]).unwrap();
+        assert_eq!(reader.header().wave_format, WaveFormat::Mulaw);
+        assert_eq!(reader.header().samples_per_second, 8000);
+        assert_eq!(reader.header().channel_mask, Some(4));
+
+        let wav = riff(&[chunk(b"fmt ", &fmt(0x55, 1, 8000, 8))]);
+        assert!(WavReader::new(&wav[..]).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_fmt_chunk() {
+        let mut wav = b"RIFF\x00\x00\x00\x00WAVEfmt ".to_vec();
+        wav.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(WavReader::new(&wav[..]).is_err());
+    }
+
+    #[test]
+    fn empty_data_chunk_is_not_streamed() {
+        let wav = riff(&[
+            chunk(b"fmt ", &fmt(1, 1, 16000, 16)),
+            chunk(b"data", &[]),
+            chunk(b"LIST", b"trailing"),
+        ]);
+        let mut reader = WavReader::new(&wav[..]).unwrap();
+        assert_eq!(reader.header().data_len, Some(0));
+        let mut data = vec![];
+        reader.read_to_end(&mut data).unwrap();
+        assert!(data.is_empty());
+
+        // streamed WAV leaves both RIFF and data lengths unset
+        let mut streamed = riff(&[chunk(b"fmt ", &fmt(1, 1, 16000, 16)), chunk(b"data", &[])]);
+        streamed[4..8].copy_from_slice(&[0; 4]);
+        streamed.extend_from_slice(&[1, 2, 3, 4]);
+        let mut reader = WavReader::new(&streamed[..]).unwrap();
+        assert_eq!(reader.header().data_len, None);
+        let mut data = vec![];
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn written_wav_reads_back() {
+        let header = WavHeader::new(WaveFormat::Alaw, 8000, 8, 1);
+        let mut writer = WavWriter::new(io::Cursor::new(vec![]), &header).unwrap();
+        writer.write_samples(&[1, 2, 3]).unwrap();
+        let wav = writer.finalize().unwrap().into_inner();
+        let mut reader = WavReader::new(&wav[..]).unwrap();
+        assert_eq!(reader.header().wave_format, WaveFormat::Alaw);
+        assert_eq!(reader.header().data_len, Some(3));
+        let mut data = vec![];
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, vec![1, 2, 3]);
+    }
+}
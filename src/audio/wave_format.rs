@@ -0,0 +1,40 @@
+/// WaveFormat defines supported wave format tags of the audio stream in WAV container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaveFormat {
+    /// Linear PCM.
+    Pcm = 1,
+
+    /// A-law-encoded format (G.711).
+    Alaw = 6,
+
+    /// Mu-law-encoded format (G.711), typically 8kHz 8bit mono in telephony.
+    Mulaw = 7,
+
+    /// G.722-encoded format.
+    G722 = 655,
+}
+
+impl WaveFormat {
+    /// Returns wave format matching the WAV header format tag, if supported.
+    pub fn from_format_tag(format_tag: u16) -> Option<Self> {
+        match format_tag {
+            1 => Some(WaveFormat::Pcm),
+            6 => Some(WaveFormat::Alaw),
+            7 => Some(WaveFormat::Mulaw),
+            655 => Some(WaveFormat::G722),
+            _ => None,
+        }
+    }
+}
+
+impl From<WaveFormat> for u32 {
+    fn from(format: WaveFormat) -> Self {
+        format as u32
+    }
+}
+
+impl From<WaveFormat> for u16 {
+    fn from(format: WaveFormat) -> Self {
+        format as u16
+    }
+}
@@ -0,0 +1,159 @@
+use crate::audio::PushAudioOutputStreamCallbacks;
+use log::*;
+use std::io::{self, Write};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Number of chunks buffered between the synthesizer and asynchronous writer.
+const ASYNC_WRITE_CHANNEL_CAPACITY: usize = 16;
+
+/// WriteCallbacks implements *PushAudioOutputStreamCallbacks* for any blocking *Write*
+/// (file, ffmpeg stdin...). Writer is flushed and dropped when the stream is closed.
+/// When write fails error is logged and remaining audio is discarded.
+#[derive(Debug)]
+pub struct WriteCallbacks<W: Write> {
+    writer: Option<W>,
+}
+
+impl<W: Write + Send> WriteCallbacks<W> {
+    pub fn new(writer: W) -> Self {
+        WriteCallbacks {
+            writer: Some(writer),
+        }
+    }
+}
+
+impl<W: Write + Send> PushAudioOutputStreamCallbacks for WriteCallbacks<W> {
+    fn write(&mut self, data_buffer: &[u8]) -> u32 {
+        let writer = match self.writer.as_mut() {
+            Some(writer) => writer,
+            None => return 0,
+        };
+        match writer.write_all(data_buffer) {
+            Ok(()) => data_buffer.len() as u32,
+            Err(err) => {
+                error!("WriteCallbacks::write error, discarding audio: {}", err);
+                self.writer = None;
+                0
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        trace!("WriteCallbacks::close");
+        if let Some(mut writer) = self.writer.take() {
+            if let Err(err) = writer.flush() {
+                error!("WriteCallbacks::close flush error: {}", err);
+            }
+        }
+    }
+}
+
+/// SenderCallbacks implements *PushAudioOutputStreamCallbacks* by sending
+/// every chunk of synthesized audio into tokio channel. Sender is dropped
+/// when the stream is closed so that receiver observes end of the audio.
+#[derive(Debug)]
+pub struct SenderCallbacks {
+    sender: Option<mpsc::Sender<Vec<u8>>>,
+}
+
+impl SenderCallbacks {
+    pub fn new(sender: mpsc::Sender<Vec<u8>>) -> Self {
+        SenderCallbacks {
+            sender: Some(sender),
+        }
+    }
+}
+
+impl PushAudioOutputStreamCallbacks for SenderCallbacks {
+    fn write(&mut self, data_buffer: &[u8]) -> u32 {
+        let sender = match self.sender.as_ref() {
+            Some(sender) => sender,
+            None => return 0,
+        };
+        // called from native SDK thread, waiting for channel capacity
+        // applies backpressure to the synthesizer
+        if sender.blocking_send(data_buffer.to_vec()).is_err() {
+            trace!("SenderCallbacks receiver dropped, discarding audio");
+            self.sender = None;
+            return 0;
+        }
+        data_buffer.len() as u32
+    }
+
+    fn close(&mut self) {
+        trace!("SenderCallbacks::close");
+        self.sender = None;
+    }
+}
+
+/// AsyncWriteCallbacks implements *PushAudioOutputStreamCallbacks* for any tokio *AsyncWrite*
+/// (e.g. HTTP response body). Audio is handed over via bounded channel to task spawned on tokio
+/// runtime which writes it, and flushes and shuts the writer down once the stream is closed.
+#[derive(Debug)]
+pub struct AsyncWriteCallbacks {
+    inner: SenderCallbacks,
+}
+
+impl AsyncWriteCallbacks {
+    /// Spawns writing task on current tokio runtime. Returned join handle
+    /// resolves with total number of bytes written. Panics when called outside of runtime.
+    pub fn new<W>(mut writer: W) -> (Self, JoinHandle<io::Result<u64>>)
+    where
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let (tx, mut rx) = mpsc::channel::<Vec<u8>>(ASYNC_WRITE_CHANNEL_CAPACITY);
+        let task = tokio::spawn(async move {
+            let mut total = 0;
+            while let Some(chunk) = rx.recv().await {
+                writer.write_all(&chunk).await?;
+                total += chunk.len() as u64;
+            }
+            writer.flush().await?;
+            writer.shutdown().await?;
+            Ok(total)
+        });
+        (
+            AsyncWriteCallbacks {
+                inner: SenderCallbacks::new(tx),
+            },
+            task,
+        )
+    }
+}
+
+impl PushAudioOutputStreamCallbacks for AsyncWriteCallbacks {
+    fn write(&mut self, data_buffer: &[u8]) -> u32 {
+        self.inner.write(data_buffer)
+    }
+
+    fn close(&mut self) {
+        trace!("AsyncWriteCallbacks::close");
+        self.inner.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_callbacks_forward_audio() {
+        let mut callbacks = WriteCallbacks::new(vec![]);
+        assert_eq!(callbacks.write(b"abc"), 3);
+        assert_eq!(callbacks.writer.as_deref(), Some(&b"abc"[..]));
+        callbacks.close();
+        assert_eq!(callbacks.write(b"abc"), 0);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let (mut callbacks, completion) = {
+            let _guard = runtime.enter();
+            AsyncWriteCallbacks::new(io::Cursor::new(vec![]))
+        };
+        assert_eq!(callbacks.write(b"abc"), 3);
+        assert_eq!(callbacks.write(b"de"), 2);
+        callbacks.close();
+        assert_eq!(runtime.block_on(completion).unwrap().unwrap(), 5);
+    }
+}
@@ -1,5 +1,5 @@
 /// CancellationErrorCode defines error code in case that CancellationReason is Error.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CancellationErrorCode {
     /// No error.
     /// If CancellationReason is EndOfStream, CancellationErrorCode
@@ -1,5 +1,5 @@
 /// SpeechSynthesisOutputFormat defines the possible speech synthesis output audio formats.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SpeechSynthesisOutputFormat {
     /// Raw8Khz8BitMonoMULaw stands for raw-8khz-8bit-mono-mulaw
     Raw8Khz8BitMonoMULaw = 1,
@@ -129,6 +129,169 @@ pub enum SpeechSynthesisOutputFormat {
     Riff44100Hz16BitMonoPcm = 37,
 }
 
+impl SpeechSynthesisOutputFormat {
+    /// Returns name of the format as used by the service, e.g. *riff-16khz-16bit-mono-pcm*.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpeechSynthesisOutputFormat::Raw8Khz8BitMonoMULaw => "raw-8khz-8bit-mono-mulaw",
+            SpeechSynthesisOutputFormat::Riff16Khz16KbpsMonoSiren => "riff-16khz-16kbps-mono-siren",
+            SpeechSynthesisOutputFormat::Audio16Khz16KbpsMonoSiren => {
+                "audio-16khz-16kbps-mono-siren"
+            }
+            SpeechSynthesisOutputFormat::Audio16Khz32KBitRateMonoMp3 => {
+                "audio-16khz-32kbitrate-mono-mp3"
+            }
+            SpeechSynthesisOutputFormat::Audio16Khz128KBitRateMonoMp3 => {
+                "audio-16khz-128kbitrate-mono-mp3"
+            }
+            SpeechSynthesisOutputFormat::Audio16Khz64KBitRateMonoMp3 => {
+                "audio-16khz-64kbitrate-mono-mp3"
+            }
+            SpeechSynthesisOutputFormat::Audio24Khz48KBitRateMonoMp3 => {
+                "audio-24khz-48kbitrate-mono-mp3"
+            }
+            SpeechSynthesisOutputFormat::Audio24Khz96KBitRateMonoMp3 => {
+                "audio-24khz-96kbitrate-mono-mp3"
+            }
+            SpeechSynthesisOutputFormat::Audio24Khz160KBitRateMonoMp3 => {
+                "audio-24khz-160kbitrate-mono-mp3"
+            }
+            SpeechSynthesisOutputFormat::Raw16Khz16BitMonoTrueSilk => {
+                "raw-16khz-16bit-mono-truesilk"
+            }
+            SpeechSynthesisOutputFormat::Riff16Khz16BitMonoPcm => "riff-16khz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Riff8Khz16BitMonoPcm => "riff-8khz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Riff24Khz16BitMonoPcm => "riff-24khz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Riff8Khz8BitMonoMULaw => "riff-8khz-8bit-mono-mulaw",
+            SpeechSynthesisOutputFormat::Raw16Khz16BitMonoPcm => "raw-16khz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Raw24Khz16BitMonoPcm => "raw-24khz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Raw8Khz16BitMonoPcm => "raw-8khz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Ogg16Khz16BitMonoOpus => "ogg-16khz-16bit-mono-opus",
+            SpeechSynthesisOutputFormat::Ogg24Khz16BitMonoOpus => "ogg-24khz-16bit-mono-opus",
+            SpeechSynthesisOutputFormat::Raw48Khz16BitMonoPcm => "raw-48khz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Riff48Khz16BitMonoPcm => "riff-48khz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Audio48Khz96KBitRateMonoMp3 => {
+                "audio-48khz-96kbitrate-mono-mp3"
+            }
+            SpeechSynthesisOutputFormat::Audio48Khz192KBitRateMonoMp3 => {
+                "audio-48khz-192kbitrate-mono-mp3"
+            }
+            SpeechSynthesisOutputFormat::Ogg48Khz16BitMonoOpus => "ogg-48khz-16bit-mono-opus",
+            SpeechSynthesisOutputFormat::Webm16Khz16BitMonoOpus => "webm-16khz-16bit-mono-opus",
+            SpeechSynthesisOutputFormat::Webm24Khz16BitMonoOpus => "webm-24khz-16bit-mono-opus",
+            SpeechSynthesisOutputFormat::Raw24Khz16BitMonoTrueSilk => {
+                "raw-24khz-16bit-mono-truesilk"
+            }
+            SpeechSynthesisOutputFormat::Raw8Khz8BitMonoALaw => "raw-8khz-8bit-mono-alaw",
+            SpeechSynthesisOutputFormat::Riff8Khz8BitMonoALaw => "riff-8khz-8bit-mono-alaw",
+            SpeechSynthesisOutputFormat::Webm24Khz16Bit24KbpsMonoOpus => {
+                "webm-24khz-16bit-24kbps-mono-opus"
+            }
+            SpeechSynthesisOutputFormat::Audio16Khz16Bit32KbpsMonoOpus => {
+                "audio-16khz-16bit-32kbps-mono-opus"
+            }
+            SpeechSynthesisOutputFormat::Audio24Khz16Bit48KbpsMonoOpus => {
+                "audio-24khz-16bit-48kbps-mono-opus"
+            }
+            SpeechSynthesisOutputFormat::Audio24Khz16Bit24KbpsMonoOpus => {
+                "audio-24khz-16bit-24kbps-mono-opus"
+            }
+            SpeechSynthesisOutputFormat::Raw22050Hz16BitMonoPcm => "raw-22050hz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Riff22050Hz16BitMonoPcm => "riff-22050hz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Raw44100Hz16BitMonoPcm => "raw-44100hz-16bit-mono-pcm",
+            SpeechSynthesisOutputFormat::Riff44100Hz16BitMonoPcm => "riff-44100hz-16bit-mono-pcm",
+        }
+    }
+
+    /// Returns format matching the service name (e.g. *riff-16khz-16bit-mono-pcm*), if any.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "raw-8khz-8bit-mono-mulaw" => Some(SpeechSynthesisOutputFormat::Raw8Khz8BitMonoMULaw),
+            "riff-16khz-16kbps-mono-siren" => {
+                Some(SpeechSynthesisOutputFormat::Riff16Khz16KbpsMonoSiren)
+            }
+            "audio-16khz-16kbps-mono-siren" => {
+                Some(SpeechSynthesisOutputFormat::Audio16Khz16KbpsMonoSiren)
+            }
+            "audio-16khz-32kbitrate-mono-mp3" => {
+                Some(SpeechSynthesisOutputFormat::Audio16Khz32KBitRateMonoMp3)
+            }
+            "audio-16khz-128kbitrate-mono-mp3" => {
+                Some(SpeechSynthesisOutputFormat::Audio16Khz128KBitRateMonoMp3)
+            }
+            "audio-16khz-64kbitrate-mono-mp3" => {
+                Some(SpeechSynthesisOutputFormat::Audio16Khz64KBitRateMonoMp3)
+            }
+            "audio-24khz-48kbitrate-mono-mp3" => {
+                Some(SpeechSynthesisOutputFormat::Audio24Khz48KBitRateMonoMp3)
+            }
+            "audio-24khz-96kbitrate-mono-mp3" => {
+                Some(SpeechSynthesisOutputFormat::Audio24Khz96KBitRateMonoMp3)
+            }
+            "audio-24khz-160kbitrate-mono-mp3" => {
+                Some(SpeechSynthesisOutputFormat::Audio24Khz160KBitRateMonoMp3)
+            }
+            "raw-16khz-16bit-mono-truesilk" => {
+                Some(SpeechSynthesisOutputFormat::Raw16Khz16BitMonoTrueSilk)
+            }
+            "riff-16khz-16bit-mono-pcm" => Some(SpeechSynthesisOutputFormat::Riff16Khz16BitMonoPcm),
+            "riff-8khz-16bit-mono-pcm" => Some(SpeechSynthesisOutputFormat::Riff8Khz16BitMonoPcm),
+            "riff-24khz-16bit-mono-pcm" => Some(SpeechSynthesisOutputFormat::Riff24Khz16BitMonoPcm),
+            "riff-8khz-8bit-mono-mulaw" => Some(SpeechSynthesisOutputFormat::Riff8Khz8BitMonoMULaw),
+            "raw-16khz-16bit-mono-pcm" => Some(SpeechSynthesisOutputFormat::Raw16Khz16BitMonoPcm),
+            "raw-24khz-16bit-mono-pcm" => Some(SpeechSynthesisOutputFormat::Raw24Khz16BitMonoPcm),
+            "raw-8khz-16bit-mono-pcm" => Some(SpeechSynthesisOutputFormat::Raw8Khz16BitMonoPcm),
+            "ogg-16khz-16bit-mono-opus" => Some(SpeechSynthesisOutputFormat::Ogg16Khz16BitMonoOpus),
+            "ogg-24khz-16bit-mono-opus" => Some(SpeechSynthesisOutputFormat::Ogg24Khz16BitMonoOpus),
+            "raw-48khz-16bit-mono-pcm" => Some(SpeechSynthesisOutputFormat::Raw48Khz16BitMonoPcm),
+            "riff-48khz-16bit-mono-pcm" => Some(SpeechSynthesisOutputFormat::Riff48Khz16BitMonoPcm),
+            "audio-48khz-96kbitrate-mono-mp3" => {
+                Some(SpeechSynthesisOutputFormat::Audio48Khz96KBitRateMonoMp3)
+            }
+            "audio-48khz-192kbitrate-mono-mp3" => {
+                Some(SpeechSynthesisOutputFormat::Audio48Khz192KBitRateMonoMp3)
+            }
+            "ogg-48khz-16bit-mono-opus" => Some(SpeechSynthesisOutputFormat::Ogg48Khz16BitMonoOpus),
+            "webm-16khz-16bit-mono-opus" => {
+                Some(SpeechSynthesisOutputFormat::Webm16Khz16BitMonoOpus)
+            }
+            "webm-24khz-16bit-mono-opus" => {
+                Some(SpeechSynthesisOutputFormat::Webm24Khz16BitMonoOpus)
+            }
+            "raw-24khz-16bit-mono-truesilk" => {
+                Some(SpeechSynthesisOutputFormat::Raw24Khz16BitMonoTrueSilk)
+            }
+            "raw-8khz-8bit-mono-alaw" => Some(SpeechSynthesisOutputFormat::Raw8Khz8BitMonoALaw),
+            "riff-8khz-8bit-mono-alaw" => Some(SpeechSynthesisOutputFormat::Riff8Khz8BitMonoALaw),
+            "webm-24khz-16bit-24kbps-mono-opus" => {
+                Some(SpeechSynthesisOutputFormat::Webm24Khz16Bit24KbpsMonoOpus)
+            }
+            "audio-16khz-16bit-32kbps-mono-opus" => {
+                Some(SpeechSynthesisOutputFormat::Audio16Khz16Bit32KbpsMonoOpus)
+            }
+            "audio-24khz-16bit-48kbps-mono-opus" => {
+                Some(SpeechSynthesisOutputFormat::Audio24Khz16Bit48KbpsMonoOpus)
+            }
+            "audio-24khz-16bit-24kbps-mono-opus" => {
+                Some(SpeechSynthesisOutputFormat::Audio24Khz16Bit24KbpsMonoOpus)
+            }
+            "raw-22050hz-16bit-mono-pcm" => {
+                Some(SpeechSynthesisOutputFormat::Raw22050Hz16BitMonoPcm)
+            }
+            "riff-22050hz-16bit-mono-pcm" => {
+                Some(SpeechSynthesisOutputFormat::Riff22050Hz16BitMonoPcm)
+            }
+            "raw-44100hz-16bit-mono-pcm" => {
+                Some(SpeechSynthesisOutputFormat::Raw44100Hz16BitMonoPcm)
+            }
+            "riff-44100hz-16bit-mono-pcm" => {
+                Some(SpeechSynthesisOutputFormat::Riff44100Hz16BitMonoPcm)
+            }
+            _ => None,
+        }
+    }
+}
+
 impl From<SpeechSynthesisOutputFormat> for i32 {
     fn from(format: SpeechSynthesisOutputFormat) -> Self {
         format as i32
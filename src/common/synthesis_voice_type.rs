@@ -1,5 +1,6 @@
 /// SynthesisVoiceType defines the type of a synthesis voice.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SynthesisVoiceType {
     /// OnlineNeural indicates online neural voice.
     OnlineNeural = 1,
@@ -1,13 +1,25 @@
 //! Package dialog provides functionality for creating custom voice assistant applications and managing the related interaction flow.
+#[cfg(feature = "serde")]
+mod activity;
+mod activity_audio;
 mod activity_received_event;
 mod bot_framework_config;
 mod custom_commands_config;
 mod dialog_service_config;
 mod dialog_service_connector;
+mod turn_status_received_event;
 
 // re-export structs directly under dialog module
+#[cfg(feature = "serde")]
+pub use self::activity::{
+    Activity, ActivityType, Attachment, ChannelAccount, ConversationAccount, InputHint,
+};
+pub use self::activity_audio::{
+    ActivityAudio, ActivityAudioStream, DEFAULT_ACTIVITY_AUDIO_CHUNK_SIZE,
+};
 pub use self::activity_received_event::ActivityReceivedEvent;
 pub use self::bot_framework_config::BotFrameworkConfig;
 pub use self::custom_commands_config::CustomCommandsConfig;
 pub use self::dialog_service_config::DialogServiceConfig;
 pub use self::dialog_service_connector::DialogServiceConnector;
+pub use self::turn_status_received_event::TurnStatusReceivedEvent;
@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Type of the activity, e.g. *message*, *event* or *invoke*.
+/// Types not modelled explicitly are preserved in *Other*.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ActivityType {
+    Message,
+    Event,
+    Invoke,
+    Other(String),
+}
+
+impl From<String> for ActivityType {
+    fn from(activity_type: String) -> Self {
+        match activity_type.as_str() {
+            "message" => ActivityType::Message,
+            "event" => ActivityType::Event,
+            "invoke" => ActivityType::Invoke,
+            _ => ActivityType::Other(activity_type),
+        }
+    }
+}
+
+impl From<ActivityType> for String {
+    fn from(activity_type: ActivityType) -> Self {
+        match activity_type {
+            ActivityType::Message => "message".to_owned(),
+            ActivityType::Event => "event".to_owned(),
+            ActivityType::Invoke => "invoke".to_owned(),
+            ActivityType::Other(activity_type) => activity_type,
+        }
+    }
+}
+
+/// Hint sent by the bot telling the client whether it should open
+/// the microphone after the activity has been presented to the user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum InputHint {
+    /// Bot is passively ready for input but is not waiting on a response.
+    AcceptingInput,
+    /// Bot is actively expecting a response, client should start listening.
+    ExpectingInput,
+    /// Bot is not ready to receive input, client should not start listening.
+    IgnoringInput,
+    Other(String),
+}
+
+impl From<String> for InputHint {
+    fn from(input_hint: String) -> Self {
+        match input_hint.as_str() {
+            "acceptingInput" => InputHint::AcceptingInput,
+            "expectingInput" => InputHint::ExpectingInput,
+            "ignoringInput" => InputHint::IgnoringInput,
+            _ => InputHint::Other(input_hint),
+        }
+    }
+}
+
+impl From<InputHint> for String {
+    fn from(input_hint: InputHint) -> Self {
+        match input_hint {
+            InputHint::AcceptingInput => "acceptingInput".to_owned(),
+            InputHint::ExpectingInput => "expectingInput".to_owned(),
+            InputHint::IgnoringInput => "ignoringInput".to_owned(),
+            InputHint::Other(input_hint) => input_hint,
+        }
+    }
+}
+
+/// Channel account (user or bot) participating in the conversation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelAccount {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+/// Conversation the activity belongs to.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationAccount {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_group: Option<bool>,
+}
+
+/// Media or card attached to the activity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub content_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+}
+
+/// Bot Framework Activity exchanged with *DialogServiceConnector*, see
+/// [Activity schema](https://github.com/microsoft/botframework-sdk/blob/main/specs/botframework-activity/botframework-activity.md).
+/// Fields not modelled explicitly are kept in *additional_properties*
+/// so that activities survive round trip unchanged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Activity {
+    #[serde(rename = "type")]
+    pub activity_type: ActivityType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<ChannelAccount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<ChannelAccount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversation: Option<ConversationAccount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Text (or SSML) that should be spoken by the client.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speak: Option<String>,
+    /// Use *effective_input_hint* to honour Bot Framework defaults when hint is missing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_hint: Option<InputHint>,
+    /// Name of the operation for *event* and *invoke* activities.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Payload of *event* and *invoke* activities.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment_layout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_data: Option<Value>,
+    #[serde(flatten)]
+    pub additional_properties: Map<String, Value>,
+}
+
+impl Activity {
+    /// Creates empty activity of given type.
+    pub fn new(activity_type: ActivityType) -> Self {
+        Activity {
+            activity_type,
+            id: None,
+            timestamp: None,
+            channel_id: None,
+            from: None,
+            recipient: None,
+            conversation: None,
+            reply_to_id: None,
+            locale: None,
+            text: None,
+            speak: None,
+            input_hint: None,
+            name: None,
+            value: None,
+            attachments: vec![],
+            attachment_layout: None,
+            channel_data: None,
+            additional_properties: Map::new(),
+        }
+    }
+
+    /// Creates *message* activity with given text.
+    pub fn message(text: impl Into<String>) -> Self {
+        let mut activity = Activity::new(ActivityType::Message);
+        activity.text = Some(text.into());
+        activity
+    }
+
+    /// Creates *event* activity with given name and value.
+    pub fn event(name: impl Into<String>, value: Option<Value>) -> Self {
+        let mut activity = Activity::new(ActivityType::Event);
+        activity.name = Some(name.into());
+        activity.value = value;
+        activity
+    }
+
+    /// Creates *invoke* activity with given name and value.
+    pub fn invoke(name: impl Into<String>, value: Option<Value>) -> Self {
+        let mut activity = Activity::new(ActivityType::Invoke);
+        activity.name = Some(name.into());
+        activity.value = value;
+        activity
+    }
+
+    pub fn with_speak(mut self, speak: impl Into<String>) -> Self {
+        self.speak = Some(speak.into());
+        self
+    }
+
+    pub fn with_input_hint(mut self, input_hint: InputHint) -> Self {
+        self.input_hint = Some(input_hint);
+        self
+    }
+
+    pub fn with_attachment(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    pub fn with_channel_data(mut self, channel_data: Value) -> Self {
+        self.channel_data = Some(channel_data);
+        self
+    }
+
+    /// Returns input hint of the activity. When the bot did not send any,
+    /// *message* activities default to *AcceptingInput* while all other
+    /// activity types default to *IgnoringInput* since they are not
+    /// presented to the user.
+    pub fn effective_input_hint(&self) -> InputHint {
+        match (&self.input_hint, &self.activity_type) {
+            (Some(input_hint), _) => input_hint.clone(),
+            (None, ActivityType::Message) => InputHint::AcceptingInput,
+            (None, _) => InputHint::IgnoringInput,
+        }
+    }
+
+    /// Returns true if client should start listening once
+    /// the activity has been presented (i.e. after speak was played).
+    pub fn expects_input(&self) -> bool {
+        self.effective_input_hint() == InputHint::ExpectingInput
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activity_round_trip_and_input_hint() {
+        let json = r#"{"type":"message","text":"hi","speak":"<speak>hi</speak>","inputHint":"expectingInput","channelData":{"a":1},"serviceUrl":"x"}"#;
+        let activity: Activity = serde_json::from_str(json).unwrap();
+        assert_eq!(activity.activity_type, ActivityType::Message);
+        assert!(activity.expects_input());
+        assert_eq!(activity.additional_properties["serviceUrl"], "x");
+
+        let value: Value = serde_json::to_value(&activity).unwrap();
+        assert_eq!(value, serde_json::from_str::<Value>(json).unwrap());
+
+        assert_eq!(
+            Activity::message("hi").effective_input_hint(),
+            InputHint::AcceptingInput
+        );
+        assert_eq!(
+            Activity::event("ping", None).effective_input_hint(),
+            InputHint::IgnoringInput
+        );
+    }
+}
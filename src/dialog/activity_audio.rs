@@ -0,0 +1,145 @@
+use crate::audio::PullAudioOutputStream;
+use crate::common::SpeechSynthesisOutputFormat;
+use crate::error::Result;
+use futures_core::Stream;
+use log::*;
+use std::convert::TryFrom;
+use std::io::{self, Read};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
+
+/// Default number of bytes requested from underlying stream per read.
+pub const DEFAULT_ACTIVITY_AUDIO_CHUNK_SIZE: u32 = 3200;
+
+/// Bot reply audio attached to *ActivityReceivedEvent*, together with its format.
+/// Audio is produced while the bot turn is still arriving, reads block
+/// until next chunk is available and end of stream is signalled
+/// by zero length read.
+#[derive(Debug)]
+pub struct ActivityAudio {
+    pub stream: PullAudioOutputStream,
+    pub format: SpeechSynthesisOutputFormat,
+    finished: bool,
+}
+
+impl ActivityAudio {
+    pub fn new(stream: PullAudioOutputStream, format: SpeechSynthesisOutputFormat) -> Self {
+        ActivityAudio {
+            stream,
+            format,
+            finished: false,
+        }
+    }
+
+    /// Reads next chunk of at most *size* bytes. Returns *None*
+    /// once the end of the stream has been reached.
+    pub fn read_chunk(&mut self, size: u32) -> Result<Option<Vec<u8>>> {
+        if self.finished {
+            return Ok(None);
+        }
+        let chunk = self.stream.read(size)?;
+        if chunk.is_empty() {
+            self.finished = true;
+            return Ok(None);
+        }
+        Ok(Some(chunk))
+    }
+
+    /// Reads the whole audio until end of stream, passing every chunk to *sink* as soon
+    /// as it is available. Returns total number of bytes forwarded. Blocks calling thread.
+    pub fn forward<F>(mut self, chunk_size: u32, mut sink: F) -> Result<u64>
+    where
+        F: FnMut(&[u8]) -> Result<()>,
+    {
+        let mut total = 0;
+        while let Some(chunk) = self.read_chunk(chunk_size)? {
+            total += chunk.len() as u64;
+            sink(&chunk)?;
+        }
+        Ok(total)
+    }
+
+    /// Converts audio into asynchronous stream of chunks. Blocking reads are
+    /// performed on dedicated thread so the stream can be consumed (e.g. relayed
+    /// to the web client) while the bot turn is still arriving.
+    pub fn into_stream(self, chunk_size: u32) -> ActivityAudioStream {
+        let format = self.format;
+        let (tx, rx) = mpsc::channel(16);
+        std::thread::spawn(move || {
+            let mut audio = self;
+            loop {
+                match audio.read_chunk(chunk_size) {
+                    Ok(Some(chunk)) => {
+                        if tx.blocking_send(Ok(chunk)).is_err() {
+                            trace!("ActivityAudioStream receiver dropped");
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(err) => {
+                        let _ = tx.blocking_send(Err(err));
+                        break;
+                    }
+                }
+            }
+        });
+        ActivityAudioStream { format, rx }
+    }
+
+    /// Asynchronously copies the whole audio into *writer* as it arrives.
+    /// Returns total number of bytes written.
+    pub async fn pipe_to<W>(self, chunk_size: u32, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut stream = self.into_stream(chunk_size);
+        let mut total = 0;
+        while let Some(chunk) = stream.recv().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await?;
+            total += chunk.len() as u64;
+        }
+        writer.flush().await?;
+        Ok(total)
+    }
+}
+
+impl Read for ActivityAudio {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let size = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+        match self.read_chunk(size).map_err(io::Error::other)? {
+            Some(chunk) => {
+                buf[..chunk.len()].copy_from_slice(&chunk);
+                Ok(chunk.len())
+            }
+            None => Ok(0),
+        }
+    }
+}
+
+/// Asynchronous stream of bot reply audio chunks created by *ActivityAudio::into_stream*.
+#[derive(Debug)]
+pub struct ActivityAudioStream {
+    pub format: SpeechSynthesisOutputFormat,
+    rx: mpsc::Receiver<Result<Vec<u8>>>,
+}
+
+impl ActivityAudioStream {
+    /// Receives next chunk, *None* signals end of the stream.
+    pub async fn recv(&mut self) -> Option<Result<Vec<u8>>> {
+        self.rx.recv().await
+    }
+}
+
+impl Stream for ActivityAudioStream {
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
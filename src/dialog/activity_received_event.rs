@@ -1,4 +1,8 @@
 use crate::audio::PullAudioOutputStream;
+use crate::common::SpeechSynthesisOutputFormat;
+#[cfg(feature = "serde")]
+use crate::dialog::Activity;
+use crate::dialog::ActivityAudio;
 use crate::error::{convert_err, Result};
 use crate::ffi::{
     dialog_service_connector_activity_received_event_get_activity,
@@ -17,6 +21,9 @@ use std::os::raw::c_char;
 pub struct ActivityReceivedEvent {
     pub handle: SmartHandle<SPXEVENTHANDLE>,
     pub activity: String,
+    /// Format of the reply audio. *DialogServiceConnector* fills it from
+    /// *PropertyId::SpeechServiceConnectionSynthOutputFormat*, defaults to raw 16kHz 16bit mono PCM.
+    pub audio_format: SpeechSynthesisOutputFormat,
 }
 
 impl ActivityReceivedEvent {
@@ -48,10 +55,17 @@ impl ActivityReceivedEvent {
                     dialog_service_connector_activity_received_event_release,
                 ),
                 activity,
+                audio_format: SpeechSynthesisOutputFormat::Raw16Khz16BitMonoPcm,
             })
         }
     }
 
+    /// Parses received activity JSON into typed *Activity*.
+    #[cfg(feature = "serde")]
+    pub fn activity(&self) -> Result<Activity> {
+        Ok(serde_json::from_str(&self.activity)?)
+    }
+
     pub fn has_audio(&self) -> bool {
         unsafe { dialog_service_connector_activity_received_event_has_audio(self.handle.inner()) }
     }
@@ -67,4 +81,15 @@ impl ActivityReceivedEvent {
             PullAudioOutputStream::from_handle(handle.assume_init())
         }
     }
+
+    /// Returns reply audio together with its format, *None* if activity has no audio.
+    pub fn get_audio_reader(&self) -> Result<Option<ActivityAudio>> {
+        if !self.has_audio() {
+            return Ok(None);
+        }
+        Ok(Some(ActivityAudio::new(
+            self.get_audio()?,
+            self.audio_format,
+        )))
+    }
 }
@@ -1,6 +1,8 @@
 use crate::audio::AudioConfig;
-use crate::common::{PropertyCollection, PropertyId};
-use crate::dialog::{ActivityReceivedEvent, DialogServiceConfig};
+use crate::common::{PropertyCollection, PropertyId, SpeechSynthesisOutputFormat};
+#[cfg(feature = "serde")]
+use crate::dialog::Activity;
+use crate::dialog::{ActivityReceivedEvent, DialogServiceConfig, TurnStatusReceivedEvent};
 use crate::error::{convert_err, Result};
 use crate::ffi::{
     dialog_service_connector_activity_received_set_callback,
@@ -12,13 +14,16 @@ use crate::ffi::{
     dialog_service_connector_recognizing_set_callback, dialog_service_connector_send_activity,
     dialog_service_connector_session_started_set_callback,
     dialog_service_connector_session_stopped_set_callback,
+    dialog_service_connector_speech_end_detected_set_callback,
+    dialog_service_connector_speech_start_detected_set_callback,
     dialog_service_connector_start_keyword_recognition,
-    dialog_service_connector_stop_keyword_recognition, SmartHandle, SPXEVENTHANDLE, SPXHANDLE,
-    SPXPROPERTYBAGHANDLE, SPXRECOHANDLE, SPXRESULTHANDLE,
+    dialog_service_connector_stop_keyword_recognition,
+    dialog_service_connector_turn_status_received_set_callback, SmartHandle, SPXEVENTHANDLE,
+    SPXHANDLE, SPXPROPERTYBAGHANDLE, SPXRECOHANDLE, SPXRESULTHANDLE,
 };
 use crate::speech::{
-    KeywordRecognitionModel, SessionEvent, SpeechRecognitionCanceledEvent, SpeechRecognitionEvent,
-    SpeechRecognitionResult,
+    KeywordRecognitionModel, RecognitionEvent, SessionEvent, SpeechRecognitionCanceledEvent,
+    SpeechRecognitionEvent, SpeechRecognitionResult,
 };
 use log::*;
 use std::ffi::{CStr, CString};
@@ -37,10 +42,13 @@ pub struct DialogServiceConnector {
     pub handle: SmartHandle<SPXRECOHANDLE>,
     session_started_cb: Option<Box<dyn Fn(SessionEvent) + Send>>,
     session_stopped_cb: Option<Box<dyn Fn(SessionEvent) + Send>>,
+    speech_start_detected_cb: Option<Box<dyn Fn(RecognitionEvent) + Send>>,
+    speech_end_detected_cb: Option<Box<dyn Fn(RecognitionEvent) + Send>>,
     canceled_cb: Option<Box<dyn Fn(SpeechRecognitionCanceledEvent) + Send>>,
     recognizing_cb: Option<Box<dyn Fn(SpeechRecognitionEvent) + Send>>,
     recognized_cb: Option<Box<dyn Fn(SpeechRecognitionEvent) + Send>>,
     activity_received_cb: Option<Box<dyn Fn(ActivityReceivedEvent) + Send>>,
+    turn_status_received_cb: Option<Box<dyn Fn(TurnStatusReceivedEvent) + Send>>,
 }
 
 impl fmt::Debug for DialogServiceConnector {
@@ -70,10 +78,13 @@ impl DialogServiceConnector {
                 ),
                 session_started_cb: None,
                 session_stopped_cb: None,
+                speech_start_detected_cb: None,
+                speech_end_detected_cb: None,
                 canceled_cb: None,
                 recognizing_cb: None,
                 recognized_cb: None,
                 activity_received_cb: None,
+                turn_status_received_cb: None,
             })
         }
     }
@@ -140,6 +151,13 @@ impl DialogServiceConnector {
         }
     }
 
+    /// Serializes typed *Activity* and sends it to the backing dialog.
+    #[cfg(feature = "serde")]
+    pub async fn send_activity(&self, activity: &Activity) -> Result<SendActivityOutcome> {
+        let message = serde_json::to_string(activity)?;
+        self.send_activity_async(message).await
+    }
+
     /// ListenOnceAsync starts a listening session that will terminate after the first utterance.
     pub async fn listen_once_async(&self) -> Result<SpeechRecognitionResult> {
         unsafe {
@@ -197,6 +215,16 @@ impl DialogServiceConnector {
             .set_property(PropertyId::SpeechServiceAuthorizationToken, auth_token)
     }
 
+    /// Returns format of the audio attached to received activities as configured
+    /// by *PropertyId::SpeechServiceConnectionSynthOutputFormat*. Defaults to raw 16kHz 16bit mono PCM.
+    pub fn get_audio_output_format(&self) -> SpeechSynthesisOutputFormat {
+        self.properties
+            .get_property(PropertyId::SpeechServiceConnectionSynthOutputFormat, "")
+            .ok()
+            .and_then(|name| SpeechSynthesisOutputFormat::from_name(&name))
+            .unwrap_or(SpeechSynthesisOutputFormat::Raw16Khz16BitMonoPcm)
+    }
+
     pub fn get_speech_activity_template(&self) -> Result<String> {
         self.properties
             .get_property(PropertyId::ConversationSpeechActivityTemplate, "")
@@ -243,6 +271,46 @@ impl DialogServiceConnector {
         }
     }
 
+    /// Sets callback invoked when the service detects start of speech in the audio.
+    pub fn set_speech_start_detected_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(RecognitionEvent) + 'static + Send,
+    {
+        self.speech_start_detected_cb = Some(Box::new(f));
+        unsafe {
+            let ret = dialog_service_connector_speech_start_detected_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_speech_start_detected),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(
+                ret,
+                "DialogServiceConnector.set_speech_start_detected_cb error",
+            )?;
+            Ok(())
+        }
+    }
+
+    /// Sets callback invoked when the service detects end of speech in the audio.
+    pub fn set_speech_end_detected_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(RecognitionEvent) + 'static + Send,
+    {
+        self.speech_end_detected_cb = Some(Box::new(f));
+        unsafe {
+            let ret = dialog_service_connector_speech_end_detected_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_speech_end_detected),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(
+                ret,
+                "DialogServiceConnector.set_speech_end_detected_cb error",
+            )?;
+            Ok(())
+        }
+    }
+
     pub fn set_canceled_cb<F>(&mut self, f: F) -> Result<()>
     where
         F: Fn(SpeechRecognitionCanceledEvent) + 'static + Send,
@@ -275,6 +343,8 @@ impl DialogServiceConnector {
         }
     }
 
+    /// Sets callback invoked with final recognition results. When keyword recognition
+    /// is active, keyword detections are delivered here with reason *ResultReason::RecognizedKeyword*.
     pub fn set_recognized_cb<F>(&mut self, f: F) -> Result<()>
     where
         F: Fn(SpeechRecognitionEvent) + 'static + Send,
@@ -291,11 +361,12 @@ impl DialogServiceConnector {
         }
     }
 
+    /// Sets callback invoked when an activity is received from the backing dialog.
     pub fn set_activity_received_cb<F>(&mut self, f: F) -> Result<()>
     where
-        F: Fn(SpeechRecognitionEvent) + 'static + Send,
+        F: Fn(ActivityReceivedEvent) + 'static + Send,
     {
-        self.recognized_cb = Some(Box::new(f));
+        self.activity_received_cb = Some(Box::new(f));
         unsafe {
             let ret = dialog_service_connector_activity_received_set_callback(
                 self.handle.inner(),
@@ -307,6 +378,26 @@ impl DialogServiceConnector {
         }
     }
 
+    /// Sets callback invoked when the backing dialog reports completion status of a turn.
+    pub fn set_turn_status_received_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(TurnStatusReceivedEvent) + 'static + Send,
+    {
+        self.turn_status_received_cb = Some(Box::new(f));
+        unsafe {
+            let ret = dialog_service_connector_turn_status_received_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_turn_status_received),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(
+                ret,
+                "DialogServiceConnector.set_turn_status_received_cb error",
+            )?;
+            Ok(())
+        }
+    }
+
     #[allow(non_snake_case)]
     #[allow(unused_variables)]
     unsafe extern "C" fn cb_session_started(
@@ -354,6 +445,58 @@ impl DialogServiceConnector {
         }
     }
 
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_speech_start_detected(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("DialogServiceConnector::cb_speech_start_detected called");
+        let dialog_service_connector = &mut *(pvContext as *mut DialogServiceConnector);
+        if let Some(cb) = &dialog_service_connector.speech_start_detected_cb {
+            trace!("speech_start_detected_cb defined");
+            match RecognitionEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!(
+                        "DialogServiceConnector::cb_speech_start_detected error {:?}",
+                        err
+                    );
+                }
+            }
+        }
+    }
+
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_speech_end_detected(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("DialogServiceConnector::cb_speech_end_detected called");
+        let dialog_service_connector = &mut *(pvContext as *mut DialogServiceConnector);
+        if let Some(cb) = &dialog_service_connector.speech_end_detected_cb {
+            trace!("speech_end_detected_cb defined");
+            match RecognitionEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!(
+                        "DialogServiceConnector::cb_speech_end_detected error {:?}",
+                        err
+                    );
+                }
+            }
+        }
+    }
+
     #[allow(non_snake_case)]
     #[allow(unused_variables)]
     unsafe extern "C" fn cb_canceled(
@@ -435,7 +578,8 @@ impl DialogServiceConnector {
         if let Some(cb) = &dialog_service_connector.activity_received_cb {
             trace!("cb_activity_received defined");
             match ActivityReceivedEvent::from_handle(hevent) {
-                Ok(event) => {
+                Ok(mut event) => {
+                    event.audio_format = dialog_service_connector.get_audio_output_format();
                     trace!("calling cb with event {:?}", event);
                     cb(event);
                 }
@@ -448,4 +592,30 @@ impl DialogServiceConnector {
             }
         }
     }
+
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_turn_status_received(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("DialogServiceConnector::cb_turn_status_received called");
+        let dialog_service_connector = &mut *(pvContext as *mut DialogServiceConnector);
+        if let Some(cb) = &dialog_service_connector.turn_status_received_cb {
+            trace!("turn_status_received_cb defined");
+            match TurnStatusReceivedEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!(
+                        "DialogServiceConnector::cb_turn_status_received error {:?}",
+                        err
+                    );
+                }
+            }
+        }
+    }
 }
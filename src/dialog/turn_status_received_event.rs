@@ -0,0 +1,97 @@
+use crate::error::{convert_err, Result};
+use crate::ffi::{
+    dialog_service_connector_turn_status_received_get_conversation_id,
+    dialog_service_connector_turn_status_received_get_conversation_id_size,
+    dialog_service_connector_turn_status_received_get_interaction_id,
+    dialog_service_connector_turn_status_received_get_interaction_id_size,
+    dialog_service_connector_turn_status_received_get_status,
+    dialog_service_connector_turn_status_received_release, SmartHandle, SPXEVENTHANDLE,
+};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+/// Event passed into callback registered by *DialogServiceConnector::set_turn_status_received_cb*.
+/// Signals that the dialog backend finished processing a turn (interaction).
+#[derive(Debug)]
+pub struct TurnStatusReceivedEvent {
+    pub handle: SmartHandle<SPXEVENTHANDLE>,
+    /// Identifier of the interaction the turn status relates to.
+    pub interaction_id: String,
+    /// Identifier of the conversation the turn status relates to.
+    pub conversation_id: String,
+    /// HTTP-like status code reported by the backend for the turn, e.g. 200 for success.
+    pub status: i32,
+}
+
+impl TurnStatusReceivedEvent {
+    /// # Safety
+    /// `handle` must be a valid handle to a live turn status received event.
+    pub unsafe fn from_handle(handle: SPXEVENTHANDLE) -> Result<TurnStatusReceivedEvent> {
+        unsafe {
+            let mut size = 0;
+            let mut ret = dialog_service_connector_turn_status_received_get_interaction_id_size(
+                handle, &mut size,
+            );
+            convert_err(
+                ret,
+                "TurnStatusReceivedEvent::from_handle(get interaction id size) error",
+            )?;
+            let mut buf_vec = vec![0u8; size + 1];
+            let c_buf: *mut c_char = &mut buf_vec[..] as *const _ as *mut c_char;
+            ret = dialog_service_connector_turn_status_received_get_interaction_id(
+                handle,
+                c_buf,
+                size + 1,
+            );
+            convert_err(
+                ret,
+                "TurnStatusReceivedEvent::from_handle(get interaction id) error",
+            )?;
+            let interaction_id = CStr::from_ptr(c_buf).to_str()?.to_owned();
+
+            let mut size = 0;
+            ret = dialog_service_connector_turn_status_received_get_conversation_id_size(
+                handle, &mut size,
+            );
+            convert_err(
+                ret,
+                "TurnStatusReceivedEvent::from_handle(get conversation id size) error",
+            )?;
+            let mut buf_vec = vec![0u8; size + 1];
+            let c_buf: *mut c_char = &mut buf_vec[..] as *const _ as *mut c_char;
+            ret = dialog_service_connector_turn_status_received_get_conversation_id(
+                handle,
+                c_buf,
+                size + 1,
+            );
+            convert_err(
+                ret,
+                "TurnStatusReceivedEvent::from_handle(get conversation id) error",
+            )?;
+            let conversation_id = CStr::from_ptr(c_buf).to_str()?.to_owned();
+
+            let mut status = 0;
+            ret = dialog_service_connector_turn_status_received_get_status(handle, &mut status);
+            convert_err(
+                ret,
+                "TurnStatusReceivedEvent::from_handle(get status) error",
+            )?;
+
+            Ok(TurnStatusReceivedEvent {
+                handle: SmartHandle::create(
+                    "TurnStatusReceivedEvent",
+                    handle,
+                    dialog_service_connector_turn_status_received_release,
+                ),
+                interaction_id,
+                conversation_id,
+                status,
+            })
+        }
+    }
+
+    /// Returns true if the backend reported successful completion of the turn (2xx status).
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
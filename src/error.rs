@@ -9,8 +9,10 @@ use std::str::Utf8Error;
 use std::string::FromUtf8Error;
 
 /// Enumeration of error root causes. Where appropriate
-/// it wraps underlying error.
+/// it wraps underlying error. Non-exhaustive since some
+/// variants depend on enabled features.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ErrorRootCause {
     ApiError(usize),
     FfiNulError(NulError),
@@ -18,6 +20,9 @@ pub enum ErrorRootCause {
     FromUtf8Error(FromUtf8Error),
     Utf8Error(Utf8Error),
     TryFromIntError(TryFromIntError),
+    IoError(std::io::Error),
+    #[cfg(feature = "serde")]
+    SerdeJsonError(serde_json::Error),
 }
 
 /// Error struct represents error than can occur
@@ -168,3 +173,22 @@ impl From<TryFromIntError> for Error {
         }
     }
 }
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error {
+        Error {
+            message: format!("std::io::Error: {}", error),
+            caused_by: ErrorRootCause::IoError(error),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Error {
+        Error {
+            message: format!("serde_json::Error: {}", error),
+            caused_by: ErrorRootCause::SerdeJsonError(error),
+        }
+    }
+}
@@ -1,14 +1,19 @@
 //! Package speech provides functionality for speech recognizers along with their related configuration and event objects.
 mod audio_data_stream;
 mod auto_detect_source_language_config;
+mod batch_synthesis;
 mod cancellation_details;
 mod embedded_speech_config;
 mod grammar_phrase;
 mod keyword_recognition_model;
+mod long_text_synthesis;
 mod phrase_list_grammar;
 mod recognition_event;
 mod session_event;
 mod source_language_config;
+mod source_language_recognition_event;
+mod source_language_recognition_result;
+mod source_language_recognizer;
 mod speech_config;
 mod speech_recognition_canceled_event;
 mod speech_recognition_event;
@@ -21,30 +26,66 @@ mod speech_synthesis_result;
 mod speech_synthesis_viseme_event;
 mod speech_synthesis_word_boundary_event;
 mod speech_synthesizer;
+pub mod ssml;
+mod ssml_voice_checker;
+mod synthesis_cache;
+mod synthesis_captions;
+mod synthesis_event_collector;
 mod synthesis_voices_result;
+mod synthesis_workers;
+mod text_stream_synthesis;
+mod viseme_animation;
+mod voice_catalog;
 mod voice_info;
 
 // re-export structs directly under speech module
 pub use self::audio_data_stream::AudioDataStream;
 pub use self::auto_detect_source_language_config::AutoDetectSourceLanguageConfig;
+pub use self::batch_synthesis::{
+    BatchItem, BatchItemResult, BatchItemStatus, BatchReport, BatchSynthesizer,
+};
 pub use self::cancellation_details::CancellationDetails;
 pub use self::embedded_speech_config::EmbeddedSpeechConfig;
 pub use self::keyword_recognition_model::KeywordRecognitionModel;
+pub use self::long_text_synthesis::{
+    concat_audio, split_ssml, split_text, LongTextSynthesisResult, LongTextSynthesizer,
+    DEFAULT_MAX_SEGMENT_CHARS,
+};
 pub use self::phrase_list_grammar::PhraseListGrammar;
 pub use self::recognition_event::RecognitionEvent;
 pub use self::session_event::SessionEvent;
 pub use self::source_language_config::SourceLanguageConfig;
+pub use self::source_language_recognition_event::SourceLanguageRecognitionEvent;
+pub use self::source_language_recognition_result::{
+    LanguageDetectionConfidence, SourceLanguageRecognitionResult,
+};
+pub use self::source_language_recognizer::SourceLanguageRecognizer;
 pub use self::speech_config::SpeechConfig;
 pub use self::speech_recognition_canceled_event::SpeechRecognitionCanceledEvent;
 pub use self::speech_recognition_event::SpeechRecognitionEvent;
 pub use self::speech_recognition_result::SpeechRecognitionResult;
 pub use self::speech_recognizer::SpeechRecognizer;
-pub use self::speech_synthesis_bookmark_event::SpeechSynthesisBookmarkEvent;
+pub use self::speech_synthesis_bookmark_event::{SpeechSynthesisBookmarkEvent, SynthesisBookmark};
 pub use self::speech_synthesis_event::SpeechSynthesisEvent;
 pub use self::speech_synthesis_request::SpeechSynthesisRequest;
 pub use self::speech_synthesis_result::SpeechSynthesisResult;
 pub use self::speech_synthesis_viseme_event::SpeechSynthesisVisemeEvent;
-pub use self::speech_synthesis_word_boundary_event::SpeechSynthesisWordBoundaryEvent;
+pub use self::speech_synthesis_word_boundary_event::{
+    SpeechSynthesisWordBoundaryEvent, SynthesisWordBoundary,
+};
 pub use self::speech_synthesizer::SpeechSynthesizer;
+pub use self::ssml_voice_checker::{SsmlIssue, SsmlVoiceChecker, VoiceCapabilities};
+pub use self::synthesis_cache::{
+    CachedSpeechSynthesizer, CachedSynthesisResult, SynthesisCache, SynthesisCacheKey,
+};
+pub use self::synthesis_captions::{
+    build_captions, to_srt, to_webvtt, Caption, CaptionCollector, CaptionConfig,
+};
 pub use self::synthesis_voices_result::SynthesisVoicesResult;
+pub use self::text_stream_synthesis::{SentenceChunker, SynthesisAudioStream};
+pub use self::viseme_animation::{
+    Viseme, VisemeAnimation, VisemeCollector, VisemeFrame, VisemeTimeline, BLEND_SHAPE_COUNT,
+    BLEND_SHAPE_NAMES, VISEME_FRAMES_PER_SECOND,
+};
+pub use self::voice_catalog::{CatalogVoice, Gender, VoiceCatalog, VoiceQuery};
 pub use self::voice_info::VoiceInfo;
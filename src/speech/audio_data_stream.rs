@@ -1,21 +1,29 @@
-use crate::common::{PropertyCollection, StreamStatus};
+use crate::common::{
+    CancellationErrorCode, CancellationReason, PropertyCollection, PropertyId, StreamStatus,
+};
 use crate::error::{convert_err, Error, ErrorRootCause, Result};
 use crate::ffi::{
     audio_data_stream_can_read_data, audio_data_stream_can_read_data_from_position,
     audio_data_stream_create_from_file, audio_data_stream_create_from_result,
-    audio_data_stream_get_position, audio_data_stream_get_property_bag,
+    audio_data_stream_detach_input, audio_data_stream_get_available_size,
+    audio_data_stream_get_canceled_error_code, audio_data_stream_get_position,
+    audio_data_stream_get_property_bag, audio_data_stream_get_reason_canceled,
     audio_data_stream_get_status, audio_data_stream_read, audio_data_stream_read_from_position,
-    audio_data_stream_save_to_wave_file, audio_data_stream_set_position, audio_stream_release,
+    audio_data_stream_release, audio_data_stream_save_to_wave_file, audio_data_stream_set_position,
     SmartHandle, SPXAUDIOSTREAMHANDLE, SPXPROPERTYBAGHANDLE,
 };
 use crate::speech::SpeechSynthesisResult;
+use std::convert::TryFrom;
 use std::ffi::CString;
+use std::io::{self, Read, Seek, SeekFrom};
 use std::mem::MaybeUninit;
 
 /// AudioDataStream represents audio data retrieved either from file
 /// or result of speech synthesis. Represents convenient option for
 /// manipulating and storing of syntehtized audio data
-/// Added in version 1.17.0
+/// Added in version 1.17.0<br/>
+/// Implements *std::io::Read* and *std::io::Seek* so it can be passed
+/// directly to decoders (e.g. *rodio::Decoder*) and other consumers.
 #[derive(Debug)]
 pub struct AudioDataStream {
     pub handle: SmartHandle<SPXAUDIOSTREAMHANDLE>,
@@ -34,7 +42,7 @@ impl AudioDataStream {
             let property_bag = PropertyCollection::from_handle(prop_bag_handle.assume_init());
 
             Ok(AudioDataStream {
-                handle: SmartHandle::create("AudioDataStream", handle, audio_stream_release),
+                handle: SmartHandle::create("AudioDataStream", handle, audio_data_stream_release),
                 properties: property_bag,
             })
         }
@@ -160,4 +168,106 @@ impl AudioDataStream {
             Ok(())
         }
     }
+
+    /// Returns number of bytes which can be read from the current position without blocking.
+    pub fn get_available_size(&self) -> u32 {
+        unsafe { audio_data_stream_get_available_size(self.handle.inner()) }
+    }
+
+    /// Stops any more data from getting to the stream, e.g. when
+    /// the rest of synthesis is not needed any more.
+    pub fn detach_input(&self) -> Result<()> {
+        unsafe {
+            let ret = audio_data_stream_detach_input(self.handle.inner());
+            convert_err(ret, "AudioDataStream.detach_input error")?;
+            Ok(())
+        }
+    }
+
+    /// Returns reason of cancellation, valid when status is *StreamStatusCanceled*.
+    pub fn get_reason_canceled(&self) -> Result<CancellationReason> {
+        unsafe {
+            let mut reason = 0;
+            let ret = audio_data_stream_get_reason_canceled(self.handle.inner(), &mut reason);
+            convert_err(ret, "AudioDataStream.get_reason_canceled error")?;
+            Ok(reason.into())
+        }
+    }
+
+    /// Returns error code of cancellation, valid when status is *StreamStatusCanceled*.
+    pub fn get_canceled_error_code(&self) -> Result<CancellationErrorCode> {
+        unsafe {
+            let mut error_code = 0;
+            let ret =
+                audio_data_stream_get_canceled_error_code(self.handle.inner(), &mut error_code);
+            convert_err(ret, "AudioDataStream.get_canceled_error_code error")?;
+            Ok(error_code.into())
+        }
+    }
+
+    /// Returns detailed description of cancellation error.
+    pub fn get_canceled_error_details(&self) -> Result<String> {
+        self.properties
+            .get_property(PropertyId::CancellationDetailsReasonDetailedText, "")
+    }
+
+    pub fn get_property(&self, id: PropertyId) -> Result<String> {
+        self.properties.get_property(id, "")
+    }
+
+    pub fn set_property(&mut self, id: PropertyId, value: String) -> Result<()> {
+        self.properties.set_property(id, value)
+    }
+
+    /// Returns total length of the stream. Known only once the stream contains all data.
+    fn len(&self) -> io::Result<u64> {
+        match self.get_status().map_err(io::Error::other)? {
+            StreamStatus::StreamStatusAllData => {
+                let offset = self.get_offset().map_err(io::Error::other)?;
+                Ok(u64::from(offset) + u64::from(self.get_available_size()))
+            }
+            status => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("AudioDataStream length is not known in status {:?}", status),
+            )),
+        }
+    }
+}
+
+impl Read for AudioDataStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let max = buf.len().min(u32::MAX as usize);
+        let filled = AudioDataStream::read(self, &mut buf[..max]).map_err(io::Error::other)?;
+        Ok(filled as usize)
+    }
+}
+
+impl Seek for AudioDataStream {
+    /// Seeking relative to the end requires the stream to contain all data
+    /// (*StreamStatusAllData*), e.g. stream created from completed synthesis result.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, delta) = match pos {
+            SeekFrom::Start(offset) => (0, i64::try_from(offset).unwrap_or(i64::MAX)),
+            SeekFrom::Current(delta) => (
+                u64::from(self.get_offset().map_err(io::Error::other)?),
+                delta,
+            ),
+            SeekFrom::End(delta) => (self.len()?, delta),
+        };
+        let offset = i64::try_from(base)
+            .ok()
+            .and_then(|base| base.checked_add(delta))
+            .and_then(|offset| u32::try_from(offset).ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "invalid seek to a negative or overflowing position",
+                )
+            })?;
+        self.set_offset(offset).map_err(io::Error::other)?;
+        Ok(u64::from(offset))
+    }
 }
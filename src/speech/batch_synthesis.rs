@@ -0,0 +1,574 @@
+use crate::common::{CancellationErrorCode, ResultReason, SpeechSynthesisOutputFormat};
+use crate::error::Result;
+use crate::speech::synthesis_workers::{run_workers, WorkQueue};
+use crate::speech::{CancellationDetails, SpeechSynthesizer};
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+
+/// Single prompt of *BatchSynthesizer*, audio is written to *output_path*.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchItem {
+    pub id: String,
+    /// Plain text or SSML, see *ssml*.
+    pub input: String,
+    pub ssml: bool,
+    pub voice: String,
+    pub output_format: SpeechSynthesisOutputFormat,
+    pub output_path: PathBuf,
+}
+
+impl BatchItem {
+    pub fn text<P: Into<PathBuf>>(
+        id: &str,
+        text: &str,
+        voice: &str,
+        output_format: SpeechSynthesisOutputFormat,
+        output_path: P,
+    ) -> Self {
+        BatchItem {
+            id: id.to_owned(),
+            input: text.to_owned(),
+            ssml: false,
+            voice: voice.to_owned(),
+            output_format,
+            output_path: output_path.into(),
+        }
+    }
+
+    pub fn ssml<P: Into<PathBuf>>(
+        id: &str,
+        ssml: &str,
+        voice: &str,
+        output_format: SpeechSynthesisOutputFormat,
+        output_path: P,
+    ) -> Self {
+        BatchItem {
+            ssml: true,
+            ..BatchItem::text(id, ssml, voice, output_format, output_path)
+        }
+    }
+
+    /// Parses CSV manifest with header row naming columns *id*, *voice*, *output_format*
+    /// (e.g. *riff-24khz-16bit-mono-pcm*), *output_path* and *text* or *ssml*.
+    /// Fields may be quoted, quoted fields can contain commas, quotes ("") and newlines.
+    pub fn from_csv_manifest(csv: &str) -> Result<Vec<BatchItem>> {
+        let mut rows = parse_csv(csv)?.into_iter();
+        let header = rows.next().unwrap_or_default();
+        let column = |name: &str| header.iter().position(|h| h.trim() == name);
+        let (id, voice, output_format, output_path) = match (
+            column("id"),
+            column("voice"),
+            column("output_format"),
+            column("output_path"),
+        ) {
+            (Some(id), Some(voice), Some(output_format), Some(output_path)) => {
+                (id, voice, output_format, output_path)
+            }
+            _ => {
+                return Err(invalid_manifest(
+                    "header must contain id, voice, output_format and output_path columns",
+                ))
+            }
+        };
+        let (text, ssml) = (column("text"), column("ssml"));
+        rows.map(|row| {
+            let field = |index: Option<usize>| {
+                index
+                    .and_then(|index| row.get(index))
+                    .filter(|value| !value.is_empty())
+                    .cloned()
+            };
+            ManifestEntry {
+                id: field(Some(id)).unwrap_or_default(),
+                text: field(text),
+                ssml: field(ssml),
+                voice: field(Some(voice)).unwrap_or_default(),
+                output_format: field(Some(output_format)).unwrap_or_default(),
+                output_path: field(Some(output_path)).unwrap_or_default(),
+            }
+            .into_item()
+        })
+        .collect()
+    }
+
+    /// Parses JSON manifest, array of objects with the same fields as CSV manifest.
+    #[cfg(feature = "serde")]
+    pub fn from_json_manifest(json: &str) -> Result<Vec<BatchItem>> {
+        let entries: Vec<ManifestEntry> = serde_json::from_str(json)?;
+        entries.into_iter().map(ManifestEntry::into_item).collect()
+    }
+}
+
+/// Item of CSV or JSON manifest.
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+struct ManifestEntry {
+    id: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    text: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    ssml: Option<String>,
+    voice: String,
+    output_format: String,
+    output_path: String,
+}
+
+impl ManifestEntry {
+    fn into_item(self) -> Result<BatchItem> {
+        if self.id.is_empty() || self.voice.is_empty() || self.output_path.is_empty() {
+            return Err(invalid_manifest(&format!(
+                "item {:?} must have id, voice and output_path",
+                self.id
+            )));
+        }
+        let output_format = SpeechSynthesisOutputFormat::from_name(self.output_format.trim())
+            .ok_or_else(|| {
+                invalid_manifest(&format!(
+                    "item {} has unknown output format {:?}",
+                    self.id, self.output_format
+                ))
+            })?;
+        match (self.text, self.ssml) {
+            (Some(text), None) => Ok(BatchItem::text(
+                &self.id,
+                &text,
+                &self.voice,
+                output_format,
+                self.output_path,
+            )),
+            (None, Some(ssml)) => Ok(BatchItem::ssml(
+                &self.id,
+                &ssml,
+                &self.voice,
+                output_format,
+                self.output_path,
+            )),
+            _ => Err(invalid_manifest(&format!(
+                "item {} must have either text or ssml",
+                self.id
+            ))),
+        }
+    }
+}
+
+fn invalid_manifest(msg: &str) -> crate::error::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid batch manifest: {}", msg),
+    )
+    .into()
+}
+
+/// Splits CSV into rows of fields, empty lines are skipped.
+fn parse_csv(csv: &str) -> Result<Vec<Vec<String>>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|field| !field.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                }
+                row.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(invalid_manifest("unterminated quoted field"));
+    }
+    row.push(field);
+    if row.iter().any(|field| !field.is_empty()) {
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Outcome of *BatchItem* synthesis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchItemStatus {
+    Succeeded {
+        audio_duration: Duration,
+        audio_bytes: usize,
+    },
+    /// *error_code* is set when the synthesis was canceled by the service.
+    Failed {
+        error_code: Option<CancellationErrorCode>,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchItemResult {
+    pub id: String,
+    pub output_path: PathBuf,
+    /// Number of synthesis attempts including retries of throttled requests.
+    pub attempts: u32,
+    pub status: BatchItemStatus,
+}
+
+impl BatchItemResult {
+    pub fn is_success(&self) -> bool {
+        matches!(self.status, BatchItemStatus::Succeeded { .. })
+    }
+}
+
+/// Results of *BatchSynthesizer::run* in the order of items.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchReport {
+    pub items: Vec<BatchItemResult>,
+}
+
+impl BatchReport {
+    pub fn succeeded(&self) -> impl Iterator<Item = &BatchItemResult> {
+        self.items.iter().filter(|item| item.is_success())
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &BatchItemResult> {
+        self.items.iter().filter(|item| !item.is_success())
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.items.iter().all(BatchItemResult::is_success)
+    }
+
+    /// Renders the report as CSV, one row per item.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("id,output_path,status,attempts,audio_duration_ms,error_code,message\n");
+        for item in &self.items {
+            let (status, duration, error_code, message) = match &item.status {
+                BatchItemStatus::Succeeded { audio_duration, .. } => (
+                    "succeeded",
+                    audio_duration.as_millis().to_string(),
+                    String::new(),
+                    "",
+                ),
+                BatchItemStatus::Failed {
+                    error_code,
+                    message,
+                } => (
+                    "failed",
+                    String::new(),
+                    error_code
+                        .map(|code| format!("{:?}", code))
+                        .unwrap_or_default(),
+                    message.as_str(),
+                ),
+            };
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                escape_csv(&item.id),
+                escape_csv(&item.output_path.to_string_lossy()),
+                status,
+                item.attempts,
+                duration,
+                error_code,
+                escape_csv(message)
+            );
+        }
+        csv
+    }
+}
+
+type BatchSynthesizerFactory =
+    dyn Fn(&str, SpeechSynthesisOutputFormat) -> Result<SpeechSynthesizer> + Send + Sync;
+
+/// BatchSynthesizer synthesizes many items over a limited number of workers. Each worker
+/// creates synthesizers by provided factory, one per voice and output format it encounters
+/// (they should not play audio to speaker). Syntheses canceled because of throttling
+/// (*TooManyRequests*, *ServiceUnavailable*) are retried with exponential backoff,
+/// other failures are reported in *BatchReport* without stopping the batch.
+pub struct BatchSynthesizer {
+    factory: Arc<BatchSynthesizerFactory>,
+    concurrency: usize,
+    retry: RetryPolicy,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl fmt::Debug for BatchSynthesizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BatchSynthesizer")
+            .field("concurrency", &self.concurrency)
+            .field("retry", &self.retry)
+            .finish()
+    }
+}
+
+impl BatchSynthesizer {
+    pub fn new<F>(factory: F) -> Self
+    where
+        F: Fn(&str, SpeechSynthesisOutputFormat) -> Result<SpeechSynthesizer>
+            + Send
+            + Sync
+            + 'static,
+    {
+        BatchSynthesizer {
+            factory: Arc::new(factory),
+            concurrency: 4,
+            retry: RetryPolicy {
+                max_retries: 5,
+                initial_backoff: Duration::from_secs(1),
+                max_backoff: Duration::from_secs(30),
+            },
+        }
+    }
+
+    /// Number of items synthesized concurrently, each worker uses its own synthesizers.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Retries of throttled synthesis, backoff starts at *initial_backoff*
+    /// and doubles with each retry up to *max_backoff*.
+    pub fn with_retries(
+        mut self,
+        max_retries: u32,
+        initial_backoff: Duration,
+        max_backoff: Duration,
+    ) -> Self {
+        self.retry = RetryPolicy {
+            max_retries,
+            initial_backoff,
+            max_backoff: max_backoff.max(initial_backoff),
+        };
+        self
+    }
+
+    /// Items which could not be synthesized, also because their worker panicked,
+    /// are reported as failed.
+    pub async fn run(&self, items: Vec<BatchItem>) -> Result<BatchReport> {
+        let ids: Vec<(String, PathBuf)> = items
+            .iter()
+            .map(|item| (item.id.clone(), item.output_path.clone()))
+            .collect();
+        let factory = self.factory.clone();
+        let retry = self.retry;
+        let outputs = run_workers(items, self.concurrency, move |queue| {
+            synthesize_items(factory.as_ref(), retry, queue);
+            Ok(())
+        })
+        .await?;
+        Ok(BatchReport {
+            items: outputs
+                .into_iter()
+                .zip(ids)
+                .map(|(output, (id, output_path))| {
+                    output.unwrap_or_else(|message| BatchItemResult {
+                        id,
+                        output_path,
+                        attempts: 0,
+                        status: failed(None, message),
+                    })
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Worker loop synthesizing items until all of them are taken.
+fn synthesize_items(
+    factory: &BatchSynthesizerFactory,
+    retry: RetryPolicy,
+    queue: &WorkQueue<BatchItem, BatchItemResult>,
+) {
+    let mut synthesizers: HashMap<(String, SpeechSynthesisOutputFormat), SpeechSynthesizer> =
+        HashMap::new();
+    while let Some((index, item)) = queue.take() {
+        let key = (item.voice.clone(), item.output_format);
+        let created = match synthesizers.get(&key) {
+            Some(_) => Ok(()),
+            None => factory(&item.voice, item.output_format).map(|synthesizer| {
+                synthesizers.insert(key.clone(), synthesizer);
+            }),
+        };
+        let (attempts, status) = match created {
+            Ok(()) => synthesize_item(&synthesizers[&key], queue, retry, item),
+            Err(err) => (0, failed(None, err)),
+        };
+        queue.complete(
+            index,
+            BatchItemResult {
+                id: item.id.clone(),
+                output_path: item.output_path.clone(),
+                attempts,
+                status,
+            },
+        );
+    }
+}
+
+/// Returns number of attempts and the outcome.
+fn synthesize_item(
+    synthesizer: &SpeechSynthesizer,
+    queue: &WorkQueue<BatchItem, BatchItemResult>,
+    retry: RetryPolicy,
+    item: &BatchItem,
+) -> (u32, BatchItemStatus) {
+    let mut backoff = retry.initial_backoff;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let result = if item.ssml {
+            queue.block_on(synthesizer.speak_ssml_async(&item.input))
+        } else {
+            queue.block_on(synthesizer.speak_text_async(&item.input))
+        };
+        let result = match result {
+            Ok(result) => result,
+            Err(err) => return (attempts, failed(None, err)),
+        };
+        if let ResultReason::Canceled = result.reason {
+            let details = match CancellationDetails::from_speech_synthesis_result(result) {
+                Ok(details) => details,
+                Err(err) => return (attempts, failed(None, err)),
+            };
+            let throttled = matches!(
+                details.error_code,
+                CancellationErrorCode::TooManyRequests | CancellationErrorCode::ServiceUnavailable
+            );
+            if throttled && attempts <= retry.max_retries {
+                std::thread::sleep(backoff);
+                backoff = (backoff * 2).min(retry.max_backoff);
+                continue;
+            }
+            return (
+                attempts,
+                failed(Some(details.error_code), details.error_details),
+            );
+        }
+        let written = item
+            .output_path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&item.output_path, &result.audio_data));
+        return match written {
+            Ok(()) => (
+                attempts,
+                BatchItemStatus::Succeeded {
+                    audio_duration: Duration::from_millis(result.audio_duration_ms),
+                    audio_bytes: result.audio_data.len(),
+                },
+            ),
+            Err(err) => (attempts, failed(None, err)),
+        };
+    }
+}
+
+fn failed<E: fmt::Display>(error_code: Option<CancellationErrorCode>, err: E) -> BatchItemStatus {
+    BatchItemStatus::Failed {
+        error_code,
+        message: err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_csv_manifest() {
+        let csv = "id,voice,output_format,output_path,text,ssml\r\n\
+                   welcome,en-US-JennyNeural,riff-24khz-16bit-mono-pcm,out/welcome.wav,\"Hello, \"\"caller\"\".\",\n\
+                   \n\
+                   menu,en-US-GuyNeural,audio-16khz-32kbitrate-mono-mp3,out/menu.mp3,,\"<speak version=\"\"1.0\"\">\nPress one.</speak>\"\n";
+        let items = BatchItem::from_csv_manifest(csv).unwrap();
+        assert_eq!(
+            items,
+            vec![
+                BatchItem::text(
+                    "welcome",
+                    "Hello, \"caller\".",
+                    "en-US-JennyNeural",
+                    SpeechSynthesisOutputFormat::Riff24Khz16BitMonoPcm,
+                    "out/welcome.wav"
+                ),
+                BatchItem::ssml(
+                    "menu",
+                    "<speak version=\"1.0\">\nPress one.</speak>",
+                    "en-US-GuyNeural",
+                    SpeechSynthesisOutputFormat::Audio16Khz32KBitRateMonoMp3,
+                    "out/menu.mp3"
+                ),
+            ]
+        );
+        assert!(BatchItem::from_csv_manifest(
+            "id,voice,output_format,output_path,text\na,v,mp3,a.mp3,hi"
+        )
+        .is_err());
+        assert!(BatchItem::from_csv_manifest("id,voice,output_path,text\na,v,a.mp3,hi").is_err());
+    }
+
+    #[test]
+    fn renders_report() {
+        let report = BatchReport {
+            items: vec![
+                BatchItemResult {
+                    id: "welcome".into(),
+                    output_path: "out/welcome.wav".into(),
+                    attempts: 2,
+                    status: BatchItemStatus::Succeeded {
+                        audio_duration: Duration::from_millis(1250),
+                        audio_bytes: 40_000,
+                    },
+                },
+                BatchItemResult {
+                    id: "menu".into(),
+                    output_path: "out/menu.mp3".into(),
+                    attempts: 1,
+                    status: BatchItemStatus::Failed {
+                        error_code: Some(CancellationErrorCode::BadRequest),
+                        message: "invalid SSML, line 1".into(),
+                    },
+                },
+            ],
+        };
+        assert!(!report.is_success());
+        assert_eq!(report.failed().count(), 1);
+        assert_eq!(
+            report.to_csv(),
+            "id,output_path,status,attempts,audio_duration_ms,error_code,message\n\
+             welcome,out/welcome.wav,succeeded,2,1250,,\n\
+             menu,out/menu.mp3,failed,1,,BadRequest,\"invalid SSML, line 1\"\n"
+        );
+    }
+}
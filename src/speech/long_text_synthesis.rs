@@ -0,0 +1,676 @@
+use crate::audio::{WavReader, WavWriter};
+use crate::common::{PropertyId, ResultReason};
+use crate::error::{Error, ErrorRootCause, Result};
+use crate::speech::synthesis_event_collector::EventCollector;
+use crate::speech::synthesis_workers::{run_workers, WorkQueue};
+use crate::speech::{SentenceChunker, SpeechSynthesizer, SynthesisBookmark, SynthesisWordBoundary};
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, Cursor, Read};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default maximal number of characters of text synthesized by single request.
+pub const DEFAULT_MAX_SEGMENT_CHARS: usize = 2000;
+
+const TICKS_PER_SECOND: u64 = 10_000_000;
+const TICKS_PER_MS: u64 = 10_000;
+
+/// SSML elements whose content must not be split into different segments.
+const NO_SPLIT_ELEMENTS: &[&str] = &["say-as", "phoneme", "sub", "audio"];
+/// Empty SSML elements which apply to the whole parent element
+/// and must be repeated in every segment of the parent.
+const STICKY_ELEMENTS: &[&str] = &[
+    "lexicon",
+    "mstts:backgroundaudio",
+    "mstts:viseme",
+    "mstts:silence",
+];
+
+/// Splits plain text into segments of at most *max_chars* characters at paragraph
+/// and sentence boundaries. Sentences longer than *max_chars* are split at phrase
+/// boundaries or whitespace. Concatenation of the segments equals the input.
+pub fn split_text(text: &str, max_chars: usize) -> Vec<String> {
+    split_text_with_offsets(text, max_chars)
+        .into_iter()
+        .map(|(segment, _)| segment)
+        .collect()
+}
+
+/// Returns segments together with their character offsets in *text*.
+fn split_text_with_offsets(text: &str, max_chars: usize) -> Vec<(String, usize)> {
+    let mut segments = vec![];
+    let mut current = String::new();
+    let mut current_len = 0;
+    let mut start = 0;
+    for sentence in sentences(text, max_chars) {
+        let len = sentence.chars().count();
+        if !current.trim().is_empty() && current_len + len > max_chars {
+            segments.push((std::mem::take(&mut current), start));
+            start += current_len;
+            current_len = 0;
+        }
+        current.push_str(&sentence);
+        current_len += len;
+    }
+    if !current.trim().is_empty() {
+        segments.push((current, start));
+    }
+    segments
+}
+
+/// Splits text into sentences, concatenation of the sentences equals *text*.
+fn sentences(text: &str, max_chars: usize) -> Vec<String> {
+    let mut sentences = SentenceChunker::new(1, max_chars).push(text);
+    let consumed: usize = sentences.iter().map(String::len).sum();
+    if consumed < text.len() {
+        sentences.push(text[consumed..].to_owned());
+    }
+    sentences
+}
+
+/// Splits SSML document into standalone SSML documents each containing at most *max_chars*
+/// characters of text (unless single sentence is longer). Documents are split at sentence
+/// boundaries only, elements open at the split point are closed and reopened in the
+/// following document, so tags are never broken. Content of *say-as*, *phoneme*, *sub*
+/// and *audio* is never split and *lexicon* elements are repeated in every document.
+pub fn split_ssml(ssml: &str, max_chars: usize) -> Result<Vec<String>> {
+    let (speak_start, body) = parse_root(ssml)?;
+    let mut splitter = SsmlSplitter::new(speak_start, max_chars);
+    let mut rest = body;
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            let end = find_end(rest, "-->")?;
+            splitter.current.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if rest.starts_with("<![CDATA[") {
+            let end = find_end(rest, "]]>")?;
+            splitter.push_text_unit(&rest[..end]);
+            rest = &rest[end..];
+        } else if rest.starts_with('<') {
+            let end = tag_end(rest)?;
+            let tag = &rest[..end];
+            if let Some(name) = tag_name(tag).filter(|name| NO_SPLIT_ELEMENTS.contains(name)) {
+                // whole content goes into one segment, split before it if it does not fit
+                if !tag.starts_with("</") && !tag.ends_with("/>") {
+                    let content_end = rest.find(&format!("</{}>", name)).unwrap_or(rest.len());
+                    splitter.reserve(text_len(&rest[end..content_end]));
+                }
+            }
+            splitter.push_tag(tag)?;
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            splitter.push_text(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+    splitter.finish()
+}
+
+fn invalid_ssml(message: String) -> Error {
+    io::Error::new(io::ErrorKind::InvalidData, message).into()
+}
+
+/// Returns *speak* start tag and content of the root element.
+fn parse_root(ssml: &str) -> Result<(&str, &str)> {
+    let mut rest = ssml.trim_start();
+    while rest.starts_with("<?") || rest.starts_with("<!--") {
+        let end = if rest.starts_with("<?") {
+            find_end(rest, "?>")?
+        } else {
+            find_end(rest, "-->")?
+        };
+        rest = rest[end..].trim_start();
+    }
+    if tag_name(rest) != Some("speak") {
+        return Err(invalid_ssml("SSML root element must be speak".to_owned()));
+    }
+    let start_end = tag_end(rest)?;
+    let body_end = rest
+        .rfind("</speak>")
+        .filter(|end| *end >= start_end && rest[end + "</speak>".len()..].trim().is_empty())
+        .ok_or_else(|| invalid_ssml("speak element is not closed".to_owned()))?;
+    Ok((&rest[..start_end], &rest[start_end..body_end]))
+}
+
+/// Returns index just after *terminator*.
+fn find_end(text: &str, terminator: &str) -> Result<usize> {
+    text.find(terminator)
+        .map(|index| index + terminator.len())
+        .ok_or_else(|| invalid_ssml(format!("missing {} in SSML", terminator)))
+}
+
+/// Returns index just after the tag starting at the beginning of *text*.
+fn tag_end(text: &str) -> Result<usize> {
+    let mut quote = None;
+    for (index, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Ok(index + 1),
+            _ => {}
+        }
+    }
+    Err(invalid_ssml("unterminated tag in SSML".to_owned()))
+}
+
+fn tag_name(tag: &str) -> Option<&str> {
+    let name = tag.strip_prefix('<')?.trim_start_matches('/');
+    let end = name
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(name.len());
+    Some(&name[..end])
+}
+
+/// Returns number of text characters of SSML fragment, ignoring tags.
+fn text_len(fragment: &str) -> usize {
+    let mut len = 0;
+    let mut in_tag = false;
+    for c in fragment.trim().chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => len += 1,
+            _ => {}
+        }
+    }
+    len
+}
+
+fn ends_with_entity(text: &str) -> bool {
+    text.strip_suffix(';')
+        .and_then(|text| text.rfind('&').map(|start| &text[start + 1..]))
+        .is_some_and(|name| {
+            !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '#')
+        })
+}
+
+struct OpenElement {
+    name: String,
+    start_tag: String,
+    /// Sticky empty elements which must follow the start tag in every segment.
+    sticky: Vec<String>,
+}
+
+struct SsmlSplitter<'a> {
+    speak_start: &'a str,
+    max_chars: usize,
+    segments: Vec<String>,
+    current: String,
+    text_len: usize,
+    stack: Vec<OpenElement>,
+    root_sticky: Vec<String>,
+}
+
+impl<'a> SsmlSplitter<'a> {
+    fn new(speak_start: &'a str, max_chars: usize) -> Self {
+        SsmlSplitter {
+            speak_start,
+            max_chars,
+            segments: vec![],
+            current: speak_start.to_owned(),
+            text_len: 0,
+            stack: vec![],
+            root_sticky: vec![],
+        }
+    }
+
+    fn push_tag(&mut self, tag: &str) -> Result<()> {
+        let name = tag_name(tag).unwrap_or_default().to_owned();
+        self.current.push_str(tag);
+        if tag.starts_with("</") {
+            match self.stack.pop() {
+                Some(element) if element.name == name => Ok(()),
+                _ => Err(invalid_ssml(format!("unexpected end tag {}", tag))),
+            }
+        } else if tag.starts_with("<?") || tag.starts_with("<!") {
+            Ok(())
+        } else if tag.ends_with("/>") {
+            if STICKY_ELEMENTS.contains(&name.as_str()) {
+                match self.stack.last_mut() {
+                    Some(element) => element.sticky.push(tag.to_owned()),
+                    None => self.root_sticky.push(tag.to_owned()),
+                }
+            }
+            Ok(())
+        } else {
+            self.stack.push(OpenElement {
+                name,
+                start_tag: tag.to_owned(),
+                sticky: vec![],
+            });
+            Ok(())
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let mut pending = String::new();
+        for sentence in sentences(text, usize::MAX) {
+            pending.push_str(&sentence);
+            // semicolon of entity reference (e.g. &amp;) is not end of phrase
+            if !ends_with_entity(&pending) {
+                self.push_text_unit(&std::mem::take(&mut pending));
+            }
+        }
+        if !pending.is_empty() {
+            self.push_text_unit(&pending);
+        }
+    }
+
+    fn push_text_unit(&mut self, text: &str) {
+        let len = text.trim().chars().count();
+        self.reserve(len);
+        self.current.push_str(text);
+        self.text_len += len;
+    }
+
+    /// Starts new segment if text of *len* characters does not fit into the current one.
+    fn reserve(&mut self, len: usize) {
+        if len > 0 && self.text_len > 0 && self.text_len + len > self.max_chars && self.can_split()
+        {
+            self.split();
+        }
+    }
+
+    fn can_split(&self) -> bool {
+        !self
+            .stack
+            .iter()
+            .any(|element| NO_SPLIT_ELEMENTS.contains(&element.name.as_str()))
+    }
+
+    fn split(&mut self) {
+        let mut segment = std::mem::take(&mut self.current);
+        for element in self.stack.iter().rev() {
+            segment.push_str("</");
+            segment.push_str(&element.name);
+            segment.push('>');
+        }
+        segment.push_str("</speak>");
+        self.segments.push(segment);
+
+        self.current.push_str(self.speak_start);
+        for sticky in &self.root_sticky {
+            self.current.push_str(sticky);
+        }
+        for element in &self.stack {
+            self.current.push_str(&element.start_tag);
+            for sticky in &element.sticky {
+                self.current.push_str(sticky);
+            }
+        }
+        self.text_len = 0;
+    }
+
+    fn finish(mut self) -> Result<Vec<String>> {
+        if let Some(element) = self.stack.last() {
+            return Err(invalid_ssml(format!(
+                "element {} is not closed",
+                element.name
+            )));
+        }
+        self.current.push_str("</speak>");
+        self.segments.push(self.current);
+        Ok(self.segments)
+    }
+}
+
+/// Concatenates audio of consecutive synthesis results. WAV parts are merged into single
+/// WAV with rewritten header, ID3 tags are stripped from MP3 parts so that frames follow
+/// each other, other formats (raw PCM, Ogg) are simply concatenated.
+pub fn concat_audio(parts: &[Vec<u8>]) -> Result<Vec<u8>> {
+    Ok(concat_audio_parts(parts)?.0)
+}
+
+/// Returns concatenated audio and exact durations (in ticks) of parts when known.
+fn concat_audio_parts(parts: &[Vec<u8>]) -> Result<(Vec<u8>, Vec<Option<u64>>)> {
+    match parts.first() {
+        None => Ok((vec![], vec![])),
+        Some(first) if is_wav(first) => concat_wav(parts),
+        Some(first) if is_mp3(first) => {
+            let mut audio = vec![];
+            for (index, part) in parts.iter().enumerate() {
+                audio.extend_from_slice(strip_id3(part, index == 0));
+            }
+            Ok((audio, vec![None; parts.len()]))
+        }
+        Some(_) => Ok((parts.concat(), vec![None; parts.len()])),
+    }
+}
+
+fn is_wav(data: &[u8]) -> bool {
+    data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WAVE"
+}
+
+fn is_mp3(data: &[u8]) -> bool {
+    data.starts_with(b"ID3") || (data.len() >= 2 && data[0] == 0xFF && data[1] & 0xE0 == 0xE0)
+}
+
+fn concat_wav(parts: &[Vec<u8>]) -> Result<(Vec<u8>, Vec<Option<u64>>)> {
+    let mut header = None;
+    let mut samples = vec![];
+    let mut durations = vec![];
+    for part in parts {
+        let mut reader = WavReader::new(Cursor::new(part))?;
+        let mut part_header = reader.header().clone();
+        part_header.data_len = None;
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+        durations.push(Some(
+            data.len() as u64 * TICKS_PER_SECOND
+                / u64::from(part_header.avg_bytes_per_second.max(1)),
+        ));
+        match &header {
+            None => header = Some(part_header),
+            Some(header) if *header != part_header => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "WAV parts have different formats",
+                )
+                .into())
+            }
+            Some(_) => {}
+        }
+        samples.push(data);
+    }
+    let header = header.expect("at least one part");
+    let mut writer = WavWriter::new(Cursor::new(vec![]), &header)?;
+    for data in &samples {
+        writer.write_samples(data)?;
+    }
+    Ok((writer.finalize()?.into_inner(), durations))
+}
+
+/// Strips trailing ID3v1 tag and (unless *keep_header*) leading ID3v2 tag.
+fn strip_id3(part: &[u8], keep_header: bool) -> &[u8] {
+    let mut data = part;
+    if !keep_header && data.len() >= 10 && data.starts_with(b"ID3") {
+        // tag size is syncsafe integer (7 bits per byte)
+        let size = data[6..10]
+            .iter()
+            .fold(0usize, |size, byte| (size << 7) | usize::from(byte & 0x7F));
+        let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+        data = &data[(10 + size + footer).min(data.len())..];
+    }
+    if data.len() >= 128 && data[data.len() - 128..].starts_with(b"TAG") {
+        data = &data[..data.len() - 128];
+    }
+    data
+}
+
+/// Result of *LongTextSynthesizer*, offsets of word boundaries and
+/// bookmarks are rebased onto the timeline of the combined audio.
+#[derive(Debug, Clone)]
+pub struct LongTextSynthesisResult {
+    pub audio_data: Vec<u8>,
+    pub audio_duration: Duration,
+    /// Number of synthesized segments.
+    pub segments: usize,
+    /// Word boundaries, for plain text input also text offsets refer to the whole input.
+    pub word_boundaries: Vec<SynthesisWordBoundary>,
+    pub bookmarks: Vec<SynthesisBookmark>,
+}
+
+struct Segment {
+    input: String,
+    ssml: bool,
+    /// Character offset of the segment in the input text, *None* for SSML.
+    text_offset: Option<usize>,
+}
+
+#[derive(Default)]
+struct SegmentEvents {
+    word_boundaries: Vec<SynthesisWordBoundary>,
+    bookmarks: Vec<SynthesisBookmark>,
+}
+
+struct SegmentOutput {
+    audio_data: Vec<u8>,
+    audio_duration_ms: u64,
+    events: SegmentEvents,
+}
+
+type SynthesizerFactory = dyn Fn() -> Result<SpeechSynthesizer> + Send + Sync;
+
+/// LongTextSynthesizer synthesizes texts exceeding limits of single request. Input is split
+/// by *split_text* / *split_ssml*, segments are synthesized in order (optionally several
+/// concurrently) and audio is stitched by *concat_audio*. Synthesizers are created by
+/// provided factory, one per concurrent worker, and should not play audio to speaker.
+pub struct LongTextSynthesizer {
+    factory: Arc<SynthesizerFactory>,
+    max_segment_chars: usize,
+    concurrency: usize,
+}
+
+impl fmt::Debug for LongTextSynthesizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LongTextSynthesizer")
+            .field("max_segment_chars", &self.max_segment_chars)
+            .field("concurrency", &self.concurrency)
+            .finish()
+    }
+}
+
+impl LongTextSynthesizer {
+    pub fn new<F>(factory: F) -> Self
+    where
+        F: Fn() -> Result<SpeechSynthesizer> + Send + Sync + 'static,
+    {
+        LongTextSynthesizer {
+            factory: Arc::new(factory),
+            max_segment_chars: DEFAULT_MAX_SEGMENT_CHARS,
+            concurrency: 1,
+        }
+    }
+
+    pub fn with_max_segment_chars(mut self, max_segment_chars: usize) -> Self {
+        self.max_segment_chars = max_segment_chars.max(1);
+        self
+    }
+
+    /// Number of segments synthesized concurrently, each by its own synthesizer.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub async fn synthesize_text(&self, text: &str) -> Result<LongTextSynthesisResult> {
+        let segments = split_text_with_offsets(text, self.max_segment_chars)
+            .into_iter()
+            .map(|(input, offset)| Segment {
+                input,
+                ssml: false,
+                text_offset: Some(offset),
+            })
+            .collect();
+        self.synthesize(segments).await
+    }
+
+    pub async fn synthesize_ssml(&self, ssml: &str) -> Result<LongTextSynthesisResult> {
+        let segments = split_ssml(ssml, self.max_segment_chars)?
+            .into_iter()
+            .map(|input| Segment {
+                input,
+                ssml: true,
+                text_offset: None,
+            })
+            .collect();
+        self.synthesize(segments).await
+    }
+
+    async fn synthesize(&self, segments: Vec<Segment>) -> Result<LongTextSynthesisResult> {
+        let segment_count = segments.len();
+        let text_offsets: Vec<Option<usize>> =
+            segments.iter().map(|segment| segment.text_offset).collect();
+        let factory = self.factory.clone();
+        let mut outputs = run_workers(segments, self.concurrency, move |queue| {
+            synthesize_segments(factory.as_ref(), queue)
+        })
+        .await?
+        .into_iter()
+        .collect::<std::result::Result<Vec<_>, String>>()
+        .map_err(io::Error::other)?;
+
+        let parts: Vec<Vec<u8>> = outputs
+            .iter_mut()
+            .map(|output| std::mem::take(&mut output.audio_data))
+            .collect();
+        let (audio_data, durations) = concat_audio_parts(&parts)?;
+
+        let mut offset = 0;
+        let mut word_boundaries = vec![];
+        let mut bookmarks = vec![];
+        for ((output, text_offset), duration) in
+            outputs.into_iter().zip(text_offsets).zip(durations)
+        {
+            for mut boundary in output.events.word_boundaries {
+                boundary.audio_offset += offset;
+                boundary.text_offset = match (text_offset, boundary.text_offset) {
+                    (Some(base), Some(text_offset)) => u32::try_from(base)
+                        .ok()
+                        .and_then(|base| base.checked_add(text_offset)),
+                    _ => None,
+                };
+                word_boundaries.push(boundary);
+            }
+            for mut bookmark in output.events.bookmarks {
+                bookmark.audio_offset += offset;
+                bookmarks.push(bookmark);
+            }
+            offset += duration.unwrap_or(output.audio_duration_ms * TICKS_PER_MS);
+        }
+
+        Ok(LongTextSynthesisResult {
+            audio_data,
+            audio_duration: Duration::from_nanos(offset * 100),
+            segments: segment_count,
+            word_boundaries,
+            bookmarks,
+        })
+    }
+}
+
+/// Worker loop synthesizing segments until all of them are taken.
+fn synthesize_segments(
+    factory: &SynthesizerFactory,
+    queue: &WorkQueue<Segment, SegmentOutput>,
+) -> Result<()> {
+    let mut synthesizer = factory()?;
+    let events = EventCollector::<SegmentEvents>::default();
+    let word_boundary_events = events.clone();
+    synthesizer.set_synthesizer_word_boundary_cb(move |event| {
+        word_boundary_events
+            .lock()
+            .word_boundaries
+            .push((&event).into());
+    })?;
+    let bookmark_events = events.clone();
+    synthesizer.set_synthesizer_bookmark_cb(move |event| {
+        bookmark_events.lock().bookmarks.push((&event).into());
+    })?;
+
+    while let Some((index, segment)) = queue.take() {
+        let result = if segment.ssml {
+            queue.block_on(synthesizer.speak_ssml_async(&segment.input))?
+        } else {
+            queue.block_on(synthesizer.speak_text_async(&segment.input))?
+        };
+        if let ResultReason::Canceled = result.reason {
+            let details = result
+                .properties
+                .get_property(PropertyId::CancellationDetailsReasonDetailedText, "")?;
+            return Err(Error::new(
+                format!("synthesis of segment {} canceled: {}", index, details),
+                ErrorRootCause::ApiError(0x032),
+            ));
+        }
+        let events = events.take();
+        queue.complete(
+            index,
+            SegmentOutput {
+                audio_data: result.audio_data,
+                audio_duration_ms: result.audio_duration_ms,
+                events,
+            },
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::WavHeader;
+    use crate::audio::WaveFormat;
+
+    #[test]
+    fn splits_text_into_segments() {
+        let text = "First sentence. Second sentence.\n\nNew paragraph here. Last.";
+        let segments = split_text_with_offsets(text, 35);
+        assert_eq!(
+            segments,
+            vec![
+                ("First sentence. Second sentence.".to_owned(), 0),
+                ("\n\nNew paragraph here. Last.".to_owned(), 32),
+            ]
+        );
+        assert_eq!(split_text(text, 35).concat(), text);
+    }
+
+    #[test]
+    fn splits_ssml_without_breaking_tags() {
+        let ssml = "<?xml version=\"1.0\"?>\n<speak version=\"1.0\" xml:lang=\"en-US\">\
+                    <voice name=\"en-US-AvaNeural\"><lexicon uri=\"lex.xml\"/>\
+                    <mstts:viseme type=\"FacialExpression\"/>\
+                    <prosody rate=\"fast\">One &amp; two. Three four. \
+                    <say-as interpret-as=\"date\">Five. Six.</say-as></prosody>\
+                    <bookmark mark=\"end\"/></voice></speak>";
+        let segments = split_ssml(ssml, 12).unwrap();
+        let open = "<speak version=\"1.0\" xml:lang=\"en-US\">\
+                    <voice name=\"en-US-AvaNeural\"><lexicon uri=\"lex.xml\"/>\
+                    <mstts:viseme type=\"FacialExpression\"/>\
+                    <prosody rate=\"fast\">";
+        assert_eq!(
+            segments,
+            vec![
+                format!("{}One &amp; two.</prosody></voice></speak>", open),
+                format!("{} Three four. </prosody></voice></speak>", open),
+                format!(
+                    "{}<say-as interpret-as=\"date\">Five. Six.</say-as></prosody>\
+                     <bookmark mark=\"end\"/></voice></speak>",
+                    open
+                ),
+            ]
+        );
+        assert!(split_ssml("<speak><voice>text</speak>", 10).is_err());
+        assert!(split_ssml("<voice>text</voice>", 10).is_err());
+    }
+
+    #[test]
+    fn concatenates_wav_and_mp3() {
+        let header = WavHeader::new(WaveFormat::Pcm, 16000, 16, 1);
+        let wav = |samples: &[u8]| {
+            let mut writer = WavWriter::new(Cursor::new(vec![]), &header).unwrap();
+            writer.write_samples(samples).unwrap();
+            writer.finalize().unwrap().into_inner()
+        };
+        let (audio, durations) = concat_audio_parts(&[wav(&[1; 3200]), wav(&[2; 6400])]).unwrap();
+        let mut reader = WavReader::new(Cursor::new(audio)).unwrap();
+        assert_eq!(reader.header().data_len, Some(9600));
+        let mut samples = vec![];
+        reader.read_to_end(&mut samples).unwrap();
+        assert_eq!(samples.len(), 9600);
+        assert_eq!(durations, vec![Some(1_000_000), Some(2_000_000)]);
+
+        let id3 = [b'I', b'D', b'3', 4, 0, 0, 0, 0, 0, 2, 0xAA, 0xBB];
+        let part = |frame: u8| {
+            let mut part = id3.to_vec();
+            part.extend_from_slice(&[0xFF, 0xFB, frame]);
+            part
+        };
+        let mut expected = part(1);
+        expected.extend_from_slice(&[0xFF, 0xFB, 2]);
+        assert_eq!(concat_audio(&[part(1), part(2)]).unwrap(), expected);
+    }
+}
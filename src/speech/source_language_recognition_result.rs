@@ -0,0 +1,84 @@
+use crate::common::PropertyId;
+use crate::error::Result;
+use crate::ffi::SPXRESULTHANDLE;
+use crate::speech::SpeechRecognitionResult;
+
+/// Confidence of the detected language as reported by the service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageDetectionConfidence {
+    Unknown,
+    Low,
+    Medium,
+    High,
+}
+
+impl From<&str> for LanguageDetectionConfidence {
+    fn from(confidence: &str) -> Self {
+        match confidence {
+            "Low" => LanguageDetectionConfidence::Low,
+            "Medium" => LanguageDetectionConfidence::Medium,
+            "High" => LanguageDetectionConfidence::High,
+            _ => LanguageDetectionConfidence::Unknown,
+        }
+    }
+}
+
+/// Represents result of *SourceLanguageRecognizer*, i.e. detected language without transcription.
+#[derive(Debug)]
+pub struct SourceLanguageRecognitionResult {
+    pub base: SpeechRecognitionResult,
+    /// Detected language in BCP-47 format (e.g. en-US), empty if no language was detected.
+    pub language: String,
+    pub confidence: LanguageDetectionConfidence,
+}
+
+impl SourceLanguageRecognitionResult {
+    /// # Safety
+    /// `handle` must be a valid handle to a live source language recognition result.
+    pub unsafe fn from_handle(handle: SPXRESULTHANDLE) -> Result<SourceLanguageRecognitionResult> {
+        unsafe {
+            let base = SpeechRecognitionResult::from_handle(handle)?;
+            let language = base.properties.get_property(
+                PropertyId::SpeechServiceConnectionAutoDetectSourceLanguageResult,
+                "",
+            )?;
+            let json = base
+                .properties
+                .get_property(PropertyId::SpeechServiceResponseJsonResult, "")?;
+            let confidence = json_string_value(&json, "Confidence")
+                .map(LanguageDetectionConfidence::from)
+                .unwrap_or(LanguageDetectionConfidence::Unknown);
+            Ok(SourceLanguageRecognitionResult {
+                base,
+                language,
+                confidence,
+            })
+        }
+    }
+}
+
+/// Extracts string value of the first occurrence of *key* in service JSON response.
+/// Service returns confidence as *"PrimaryLanguage":{"Language":"en-US","Confidence":"High"}*.
+fn json_string_value<'a>(json: &'a str, key: &str) -> Option<&'a str> {
+    let pattern = format!("\"{}\"", key);
+    let rest = &json[json.find(&pattern)? + pattern.len()..];
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+    let rest = rest.strip_prefix('"')?;
+    Some(&rest[..rest.find('"')?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_confidence_from_json_result() {
+        let json = r#"{"RecognitionStatus":"Success","PrimaryLanguage":{"Language":"de-DE", "Confidence" : "Medium"}}"#;
+        assert_eq!(json_string_value(json, "Language"), Some("de-DE"));
+        assert_eq!(
+            json_string_value(json, "Confidence").map(LanguageDetectionConfidence::from),
+            Some(LanguageDetectionConfidence::Medium)
+        );
+        assert_eq!(json_string_value(json, "Missing"), None);
+    }
+}
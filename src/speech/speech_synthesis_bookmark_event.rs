@@ -43,3 +43,21 @@ impl SpeechSynthesisBookmarkEvent {
         }
     }
 }
+
+/// Bookmark reached during synthesis: *text* is the mark of SSML
+/// *<bookmark mark="..."/>* element, *audio_offset* its position in the audio.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SynthesisBookmark {
+    /// Offset in the audio in 100 ns ticks.
+    pub audio_offset: u64,
+    pub text: String,
+}
+
+impl From<&SpeechSynthesisBookmarkEvent> for SynthesisBookmark {
+    fn from(event: &SpeechSynthesisBookmarkEvent) -> Self {
+        SynthesisBookmark {
+            audio_offset: event.audio_offset,
+            text: event.text.clone(),
+        }
+    }
+}
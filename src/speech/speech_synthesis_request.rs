@@ -3,12 +3,15 @@ use crate::error::{convert_err, Result};
 use crate::ffi::{
     speech_synthesis_request_create, speech_synthesis_request_finish,
     speech_synthesis_request_get_property_bag, speech_synthesis_request_release,
-    speech_synthesis_request_send_text_piece, SmartHandle, SPXPROPERTYBAGHANDLE, SPXREQUESTHANDLE,
+    speech_synthesis_request_send_text_piece, speech_synthesis_request_set_voice, SmartHandle,
+    SPXPROPERTYBAGHANDLE, SPXREQUESTHANDLE,
 };
+use crate::speech::ssml::Speak;
 use std::ffi::CString;
 use std::mem::MaybeUninit;
 
-/// Represents an input stream for speech synthesis request.
+/// Represents a speech synthesis request with its input (plain text, SSML or text stream),
+/// voice and request-level options. Allows one *SpeechSynthesizer* to serve many voices.
 #[derive(Debug)]
 pub struct SpeechSynthesisRequest {
     pub handle: SmartHandle<SPXREQUESTHANDLE>,
@@ -20,34 +23,91 @@ unsafe impl Sync for SpeechSynthesisRequest {}
 impl SpeechSynthesisRequest {
     /// Creates a speech synthesis request, with text streaming is enabled.
     pub fn new_text_streaming_request() -> Result<Self> {
+        Self::create(true, false, None)
+    }
+
+    /// Creates a speech synthesis request of plain text.
+    pub fn new_text_request(text: &str) -> Result<Self> {
+        Self::create(false, false, Some(text))
+    }
+
+    /// Creates a speech synthesis request of SSML.
+    pub fn new_ssml_request(ssml: &str) -> Result<Self> {
+        Self::create(false, true, Some(ssml))
+    }
+
+    /// Creates a speech synthesis request of validated SSML document. Request-level
+    /// pitch, rate and volume (*Prosody*), style (*ExpressAs*), custom lexicon (*Lexicon*)
+    /// and language (*Lang*) are expressed by elements of the document.
+    pub fn new_ssml_request_from_speak(speak: &Speak) -> Result<Self> {
+        Self::new_ssml_request(&speak.to_ssml()?)
+    }
+
+    fn create(text_streaming: bool, is_ssml: bool, input: Option<&str>) -> Result<Self> {
+        let c_input = input.map(CString::new).transpose()?;
+        let (input_ptr, input_len) = match &c_input {
+            Some(c_input) => (c_input.as_ptr(), c_input.as_bytes().len() as u32),
+            None => (std::ptr::null(), 0),
+        };
         unsafe {
             let mut request_handle: MaybeUninit<SPXREQUESTHANDLE> = MaybeUninit::uninit();
             let ret = speech_synthesis_request_create(
-                true,
-                false,
-                std::ptr::null(),
-                0,
+                text_streaming,
+                is_ssml,
+                input_ptr,
+                input_len,
                 request_handle.as_mut_ptr(),
             );
             convert_err(ret, "Failed to create speech synthesis request handle")?;
+            let handle = SmartHandle::create(
+                "SpeechSynthesisRequest",
+                request_handle.assume_init(),
+                speech_synthesis_request_release,
+            );
 
             let mut prop_bag_handle: MaybeUninit<SPXPROPERTYBAGHANDLE> = MaybeUninit::uninit();
             let ret = speech_synthesis_request_get_property_bag(
-                request_handle.assume_init(),
+                handle.inner(),
                 prop_bag_handle.as_mut_ptr(),
             );
             convert_err(ret, "Failed to get speech synthesis request property bag")?;
 
-            let request = Self {
-                handle: SmartHandle::create(
-                    "SpeechSynthesisRequest",
-                    request_handle.assume_init(),
-                    speech_synthesis_request_release,
-                ),
+            Ok(Self {
+                handle,
                 properties: PropertyCollection::from_handle(prop_bag_handle.assume_init()),
-            };
+            })
+        }
+    }
 
-            Ok(request)
+    /// Sets voice of this request (e.g. *en-US-AvaMultilingualNeural*),
+    /// overriding voice of *SpeechConfig*.
+    pub fn set_voice(&mut self, voice: &str) -> Result<()> {
+        self.set_voice_with_model(voice, None, None)
+    }
+
+    /// Sets voice of this request together with personal voice
+    /// speaker profile id and/or model name.
+    pub fn set_voice_with_model(
+        &mut self,
+        voice: &str,
+        personal_voice: Option<&str>,
+        model_name: Option<&str>,
+    ) -> Result<()> {
+        let c_voice = CString::new(voice)?;
+        let c_personal_voice = personal_voice.map(CString::new).transpose()?;
+        let c_model_name = model_name.map(CString::new).transpose()?;
+        unsafe {
+            let ret = speech_synthesis_request_set_voice(
+                self.handle.inner(),
+                c_voice.as_ptr(),
+                c_personal_voice
+                    .as_ref()
+                    .map_or(std::ptr::null(), |s| s.as_ptr()),
+                c_model_name
+                    .as_ref()
+                    .map_or(std::ptr::null(), |s| s.as_ptr()),
+            );
+            convert_err(ret, "SpeechSynthesisRequest.set_voice error")
         }
     }
 
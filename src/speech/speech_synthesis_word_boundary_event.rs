@@ -63,3 +63,34 @@ impl SpeechSynthesisWordBoundaryEvent {
         }
     }
 }
+
+/// Word, punctuation or sentence boundary reported during synthesis. Unlike
+/// *SpeechSynthesisWordBoundaryEvent* it can be kept after the callback returns,
+/// e.g. to build captions from boundaries of the whole synthesis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SynthesisWordBoundary {
+    /// Offset in the audio in 100 ns ticks.
+    pub audio_offset: u64,
+    /// Duration of the word in 100 ns ticks.
+    pub duration: u64,
+    /// Offset of the word in the input text (or SSML), *None* when not known.
+    pub text_offset: Option<u32>,
+    pub word_length: u32,
+    pub boundary_type: SpeechSynthesisBoundaryType,
+    pub text: String,
+}
+
+impl From<&SpeechSynthesisWordBoundaryEvent> for SynthesisWordBoundary {
+    fn from(event: &SpeechSynthesisWordBoundaryEvent) -> Self {
+        SynthesisWordBoundary {
+            audio_offset: event.audio_offset,
+            // native API reports the duration in ticks as well
+            duration: event.duration_ms,
+            // native API reports unknown text offset as -1
+            text_offset: Some(event.text_offset).filter(|offset| *offset != u32::MAX),
+            word_length: event.word_length,
+            boundary_type: event.boundary_type,
+            text: event.text.clone(),
+        }
+    }
+}
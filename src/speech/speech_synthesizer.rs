@@ -6,18 +6,21 @@ use crate::ffi::{
     synthesizer_completed_set_callback,
     synthesizer_create_speech_synthesizer_from_auto_detect_source_lang_config,
     synthesizer_create_speech_synthesizer_from_config, synthesizer_get_property_bag,
-    synthesizer_get_voices_list, synthesizer_handle_release, synthesizer_speak_ssml,
-    synthesizer_speak_text, synthesizer_start_speaking_request, synthesizer_start_speaking_ssml,
-    synthesizer_start_speaking_text, synthesizer_started_set_callback, synthesizer_stop_speaking,
+    synthesizer_get_voices_list, synthesizer_handle_release, synthesizer_speak_request,
+    synthesizer_speak_ssml, synthesizer_speak_text, synthesizer_start_speaking_request,
+    synthesizer_start_speaking_ssml, synthesizer_start_speaking_text,
+    synthesizer_started_set_callback, synthesizer_stop_speaking,
     synthesizer_synthesizing_set_callback, synthesizer_viseme_received_set_callback,
     synthesizer_word_boundary_set_callback, SmartHandle, SPXEVENTHANDLE, SPXPROPERTYBAGHANDLE,
     SPXRESULTHANDLE, SPXSYNTHHANDLE,
 };
 use crate::speech::{
-    AutoDetectSourceLanguageConfig, SpeechConfig, SpeechSynthesisBookmarkEvent,
-    SpeechSynthesisEvent, SpeechSynthesisRequest, SpeechSynthesisResult,
-    SpeechSynthesisVisemeEvent, SpeechSynthesisWordBoundaryEvent, SynthesisVoicesResult,
+    AudioDataStream, AutoDetectSourceLanguageConfig, SentenceChunker, SpeechConfig,
+    SpeechSynthesisBookmarkEvent, SpeechSynthesisEvent, SpeechSynthesisRequest,
+    SpeechSynthesisResult, SpeechSynthesisVisemeEvent, SpeechSynthesisWordBoundaryEvent,
+    SynthesisAudioStream, SynthesisVoicesResult,
 };
+use futures_core::Stream;
 use log::*;
 use std::boxed::Box;
 use std::ffi::CString;
@@ -169,6 +172,24 @@ impl SpeechSynthesizer {
         }
     }
 
+    /// Executes the speech synthesis on request (plain text or SSML with request-level
+    /// voice and options), asynchronously.
+    pub async fn speak_request_async(
+        &self,
+        request: &SpeechSynthesisRequest,
+    ) -> Result<SpeechSynthesisResult> {
+        unsafe {
+            let mut result_handle: MaybeUninit<SPXRESULTHANDLE> = MaybeUninit::uninit();
+            let ret = synthesizer_speak_request(
+                self.handle.inner(),
+                request.handle.inner(),
+                result_handle.as_mut_ptr(),
+            );
+            convert_err(ret, "SpeechSynthesizer.speak_request_async error")?;
+            SpeechSynthesisResult::from_handle(result_handle.assume_init())
+        }
+    }
+
     /// Starts the speech synthesis on plain text, asynchronously.
     /// It returns when the synthesis request is started to process
     /// (the result reason is SynthesizingAudioStarted).
@@ -227,6 +248,32 @@ impl SpeechSynthesizer {
         }
     }
 
+    /// Synthesizes text arriving as asynchronous stream of pieces (e.g. LLM tokens).
+    /// Pieces are buffered and sent to the service at sentence boundaries using
+    /// default *SentenceChunker*. Returns stream of synthesized audio chunks.
+    pub async fn speak_text_stream<S>(&self, text: S) -> Result<SynthesisAudioStream>
+    where
+        S: Stream<Item = String> + Send + Unpin + 'static,
+    {
+        self.speak_text_stream_with_chunker(text, SentenceChunker::default())
+            .await
+    }
+
+    /// Same as *speak_text_stream* with custom *SentenceChunker*.
+    pub async fn speak_text_stream_with_chunker<S>(
+        &self,
+        text: S,
+        chunker: SentenceChunker,
+    ) -> Result<SynthesisAudioStream>
+    where
+        S: Stream<Item = String> + Send + Unpin + 'static,
+    {
+        let request = SpeechSynthesisRequest::new_text_streaming_request()?;
+        let result = self.start_speaking_async(&request).await?;
+        let audio = AudioDataStream::from_speech_synthesis_result(result)?;
+        Ok(SynthesisAudioStream::spawn(request, audio, text, chunker))
+    }
+
     /// Stops the speech synthesis, asynchronously.
     /// It stops audio speech synthesis and discards any unread data in audio.PullAudioOutputStream.
     pub async fn stop_speaking_async(&self) -> Result<()> {
@@ -0,0 +1,840 @@
+//! Package ssml provides typed builder of Speech Synthesis Markup Language documents.
+//! All text and attribute values are escaped when rendered and the document is validated
+//! by *Speak::to_ssml* before it can be passed to *SpeechSynthesizer::speak_ssml_async*.
+use crate::error::{Error, Result};
+use std::io;
+use std::time::Duration;
+
+/// Longest pause supported by *Break*.
+const MAX_BREAK_TIME: Duration = Duration::from_secs(20);
+
+const RATE_KEYWORDS: &[&str] = &["x-slow", "slow", "medium", "fast", "x-fast", "default"];
+const PITCH_KEYWORDS: &[&str] = &["x-low", "low", "medium", "high", "x-high", "default"];
+const VOLUME_KEYWORDS: &[&str] = &[
+    "silent", "x-soft", "soft", "medium", "loud", "x-loud", "default",
+];
+
+/// Root *speak* element of SSML document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Speak {
+    lang: String,
+    voices: Vec<Voice>,
+}
+
+impl Speak {
+    /// Creates document of given language, e.g. *en-US*.
+    pub fn new(lang: impl Into<String>) -> Self {
+        Speak {
+            lang: lang.into(),
+            voices: vec![],
+        }
+    }
+
+    pub fn voice(mut self, voice: Voice) -> Self {
+        self.voices.push(voice);
+        self
+    }
+
+    pub fn get_lang(&self) -> &str {
+        &self.lang
+    }
+
+    pub fn get_voices(&self) -> &[Voice] {
+        &self.voices
+    }
+
+    /// Validates the document and renders it into SSML string.
+    pub fn to_ssml(&self) -> Result<String> {
+        self.validate()?;
+        let mut out = String::new();
+        out.push_str(
+            "<speak version=\"1.0\" xmlns=\"http://www.w3.org/2001/10/synthesis\" \
+             xmlns:mstts=\"https://www.w3.org/2001/mstts\"",
+        );
+        push_attribute(&mut out, "xml:lang", &self.lang);
+        out.push('>');
+        for voice in &self.voices {
+            voice.render(&mut out);
+        }
+        out.push_str("</speak>");
+        Ok(out)
+    }
+
+    /// Checks that the document is well formed and all values are supported by the service.
+    pub fn validate(&self) -> Result<()> {
+        check_not_empty("speak", "xml:lang", &self.lang)?;
+        if self.voices.is_empty() {
+            return Err(invalid("speak must contain at least one voice".to_owned()));
+        }
+        for voice in &self.voices {
+            check_not_empty("voice", "name", &voice.name)?;
+            for (index, node) in voice.children.iter().enumerate() {
+                // lexicons are applied to the whole voice, they must precede its content
+                if let Node::Lexicon(_) = node {
+                    if voice.children[..index]
+                        .iter()
+                        .any(|node| !matches!(node, Node::Lexicon(_)))
+                    {
+                        return Err(invalid(
+                            "lexicon must precede other content of voice".to_owned(),
+                        ));
+                    }
+                }
+                node.validate(false, true)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Node of SSML content, either (escaped) text or element.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Text(String),
+    Lang(Lang),
+    Prosody(Prosody),
+    Break(Break),
+    Emphasis(Emphasis),
+    SayAs(SayAs),
+    Phoneme(Phoneme),
+    Lexicon(Lexicon),
+    Bookmark(Bookmark),
+    Audio(Audio),
+    ExpressAs(ExpressAs),
+}
+
+impl Node {
+    /// Returns child nodes of container elements, empty for text and other elements.
+    pub fn get_children(&self) -> &[Node] {
+        match self {
+            Node::Lang(lang) => &lang.children,
+            Node::Prosody(prosody) => &prosody.children,
+            Node::Emphasis(emphasis) => &emphasis.children,
+            Node::Audio(audio) => &audio.children,
+            Node::ExpressAs(express_as) => &express_as.children,
+            _ => &[],
+        }
+    }
+
+    fn validate(&self, in_express_as: bool, in_voice: bool) -> Result<()> {
+        match self {
+            Node::Text(text) => check_chars("text", text),
+            Node::Lang(lang) => {
+                check_not_empty("lang", "xml:lang", &lang.lang)?;
+                validate_children(&lang.children, in_express_as)
+            }
+            Node::Prosody(prosody) => {
+                if prosody.rate.is_none() && prosody.pitch.is_none() && prosody.volume.is_none() {
+                    return Err(invalid(
+                        "prosody must have at least one of rate, pitch or volume".to_owned(),
+                    ));
+                }
+                if let Some(rate) = &prosody.rate {
+                    check_prosody_value("rate", rate, RATE_KEYWORDS, &["%", ""])?;
+                }
+                if let Some(pitch) = &prosody.pitch {
+                    check_prosody_value("pitch", pitch, PITCH_KEYWORDS, &["%", "Hz", "st"])?;
+                }
+                if let Some(volume) = &prosody.volume {
+                    check_prosody_value("volume", volume, VOLUME_KEYWORDS, &["%", ""])?;
+                }
+                validate_children(&prosody.children, in_express_as)
+            }
+            Node::Break(Break::Time(time)) if *time > MAX_BREAK_TIME => Err(invalid(format!(
+                "break time {:?} exceeds maximum of {:?}",
+                time, MAX_BREAK_TIME
+            ))),
+            Node::Break(_) => Ok(()),
+            Node::Emphasis(emphasis) => validate_children(&emphasis.children, in_express_as),
+            Node::SayAs(say_as) => {
+                check_not_empty("say-as", "interpret-as", &say_as.interpret_as)?;
+                if let Some(format) = &say_as.format {
+                    check_chars("say-as format", format)?;
+                }
+                if let Some(detail) = &say_as.detail {
+                    check_chars("say-as detail", detail)?;
+                }
+                check_chars("say-as text", &say_as.text)
+            }
+            Node::Phoneme(phoneme) => {
+                check_not_empty("phoneme", "ph", &phoneme.ph)?;
+                check_chars("phoneme text", &phoneme.text)
+            }
+            Node::Lexicon(_) if !in_voice => {
+                Err(invalid("lexicon must be direct child of voice".to_owned()))
+            }
+            Node::Lexicon(lexicon) => check_not_empty("lexicon", "uri", &lexicon.uri),
+            Node::Bookmark(bookmark) => check_not_empty("bookmark", "mark", &bookmark.mark),
+            Node::Audio(audio) => {
+                check_not_empty("audio", "src", &audio.src)?;
+                validate_children(&audio.children, in_express_as)
+            }
+            Node::ExpressAs(_) if in_express_as => {
+                Err(invalid("mstts:express-as cannot be nested".to_owned()))
+            }
+            Node::ExpressAs(express_as) => {
+                check_not_empty("mstts:express-as", "style", &express_as.style)?;
+                if let Some(degree) = express_as.style_degree {
+                    if !(0.01..=2.0).contains(&degree) {
+                        return Err(invalid(format!(
+                            "mstts:express-as styledegree {} is out of range 0.01 - 2.0",
+                            degree
+                        )));
+                    }
+                }
+                validate_children(&express_as.children, true)
+            }
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        match self {
+            Node::Text(text) => push_escaped(out, text),
+            Node::Lang(lang) => render_element(
+                out,
+                "lang",
+                &[("xml:lang", Some(&lang.lang))],
+                &lang.children,
+            ),
+            Node::Prosody(prosody) => render_element(
+                out,
+                "prosody",
+                &[
+                    ("rate", prosody.rate.as_deref()),
+                    ("pitch", prosody.pitch.as_deref()),
+                    ("volume", prosody.volume.as_deref()),
+                ],
+                &prosody.children,
+            ),
+            Node::Break(Break::Time(time)) => {
+                let time = format!("{}ms", time.as_millis());
+                render_element(out, "break", &[("time", Some(&time))], &[])
+            }
+            Node::Break(Break::Strength(strength)) => {
+                render_element(out, "break", &[("strength", Some(strength.as_str()))], &[])
+            }
+            Node::Emphasis(emphasis) => render_element(
+                out,
+                "emphasis",
+                &[("level", emphasis.level.map(EmphasisLevel::as_str))],
+                &emphasis.children,
+            ),
+            Node::SayAs(say_as) => render_text_element(
+                out,
+                "say-as",
+                &[
+                    ("interpret-as", Some(&say_as.interpret_as)),
+                    ("format", say_as.format.as_deref()),
+                    ("detail", say_as.detail.as_deref()),
+                ],
+                &say_as.text,
+            ),
+            Node::Phoneme(phoneme) => render_text_element(
+                out,
+                "phoneme",
+                &[
+                    ("alphabet", Some(phoneme.alphabet.as_str())),
+                    ("ph", Some(&phoneme.ph)),
+                ],
+                &phoneme.text,
+            ),
+            Node::Lexicon(lexicon) => {
+                render_element(out, "lexicon", &[("uri", Some(&lexicon.uri))], &[])
+            }
+            Node::Bookmark(bookmark) => {
+                render_element(out, "bookmark", &[("mark", Some(&bookmark.mark))], &[])
+            }
+            Node::Audio(audio) => {
+                render_element(out, "audio", &[("src", Some(&audio.src))], &audio.children)
+            }
+            Node::ExpressAs(express_as) => {
+                let style_degree = express_as.style_degree.map(|degree| degree.to_string());
+                render_element(
+                    out,
+                    "mstts:express-as",
+                    &[
+                        ("style", Some(&express_as.style)),
+                        ("styledegree", style_degree.as_deref()),
+                        ("role", express_as.role.map(Role::as_str)),
+                    ],
+                    &express_as.children,
+                )
+            }
+        }
+    }
+}
+
+/// *voice* element, all content of the document is spoken by some voice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Voice {
+    name: String,
+    children: Vec<Node>,
+}
+
+impl Voice {
+    /// Creates voice of given short name, e.g. *en-US-AvaMultilingualNeural*.
+    pub fn new(name: impl Into<String>) -> Self {
+        Voice {
+            name: name.into(),
+            children: vec![],
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_children(&self) -> &[Node] {
+        &self.children
+    }
+
+    fn render(&self, out: &mut String) {
+        render_element(out, "voice", &[("name", Some(&self.name))], &self.children);
+    }
+}
+
+/// *lang* element switching language of multilingual voice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lang {
+    lang: String,
+    children: Vec<Node>,
+}
+
+impl Lang {
+    pub fn new(lang: impl Into<String>) -> Self {
+        Lang {
+            lang: lang.into(),
+            children: vec![],
+        }
+    }
+
+    pub fn get_lang(&self) -> &str {
+        &self.lang
+    }
+}
+
+/// *prosody* element. Values are keywords (e.g. *fast*, *x-low*, *loud*) or numbers,
+/// relative values (e.g. *+10%*, *-2st*, *+50Hz*) are supported too.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Prosody {
+    rate: Option<String>,
+    pitch: Option<String>,
+    volume: Option<String>,
+    children: Vec<Node>,
+}
+
+impl Prosody {
+    pub fn new() -> Self {
+        Prosody::default()
+    }
+
+    pub fn rate(mut self, rate: impl Into<String>) -> Self {
+        self.rate = Some(rate.into());
+        self
+    }
+
+    pub fn pitch(mut self, pitch: impl Into<String>) -> Self {
+        self.pitch = Some(pitch.into());
+        self
+    }
+
+    pub fn volume(mut self, volume: impl Into<String>) -> Self {
+        self.volume = Some(volume.into());
+        self
+    }
+}
+
+/// Strength of *Break*.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakStrength {
+    None,
+    ExtraWeak,
+    Weak,
+    Medium,
+    Strong,
+    ExtraStrong,
+}
+
+impl BreakStrength {
+    fn as_str(self) -> &'static str {
+        match self {
+            BreakStrength::None => "none",
+            BreakStrength::ExtraWeak => "x-weak",
+            BreakStrength::Weak => "weak",
+            BreakStrength::Medium => "medium",
+            BreakStrength::Strong => "strong",
+            BreakStrength::ExtraStrong => "x-strong",
+        }
+    }
+}
+
+/// *break* element, pause of given duration (up to 20 seconds) or strength.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Break {
+    Time(Duration),
+    Strength(BreakStrength),
+}
+
+/// Level of *Emphasis*.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmphasisLevel {
+    Reduced,
+    None,
+    Moderate,
+    Strong,
+}
+
+impl EmphasisLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            EmphasisLevel::Reduced => "reduced",
+            EmphasisLevel::None => "none",
+            EmphasisLevel::Moderate => "moderate",
+            EmphasisLevel::Strong => "strong",
+        }
+    }
+}
+
+/// *emphasis* element.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Emphasis {
+    level: Option<EmphasisLevel>,
+    children: Vec<Node>,
+}
+
+impl Emphasis {
+    pub fn new() -> Self {
+        Emphasis::default()
+    }
+
+    pub fn level(mut self, level: EmphasisLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+}
+
+/// *say-as* element, e.g. *SayAs::new("date", "2024-05-01").format("ymd")*.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SayAs {
+    interpret_as: String,
+    format: Option<String>,
+    detail: Option<String>,
+    text: String,
+}
+
+impl SayAs {
+    pub fn new(interpret_as: impl Into<String>, text: impl Into<String>) -> Self {
+        SayAs {
+            interpret_as: interpret_as.into(),
+            format: None,
+            detail: None,
+            text: text.into(),
+        }
+    }
+
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// Phonetic alphabet of *Phoneme*.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhoneticAlphabet {
+    Ipa,
+    Sapi,
+    Ups,
+    XSampa,
+}
+
+impl PhoneticAlphabet {
+    fn as_str(self) -> &'static str {
+        match self {
+            PhoneticAlphabet::Ipa => "ipa",
+            PhoneticAlphabet::Sapi => "sapi",
+            PhoneticAlphabet::Ups => "ups",
+            PhoneticAlphabet::XSampa => "x-sampa",
+        }
+    }
+}
+
+/// *phoneme* element specifying pronunciation of *text*.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phoneme {
+    alphabet: PhoneticAlphabet,
+    ph: String,
+    text: String,
+}
+
+impl Phoneme {
+    pub fn new(alphabet: PhoneticAlphabet, ph: impl Into<String>, text: impl Into<String>) -> Self {
+        Phoneme {
+            alphabet,
+            ph: ph.into(),
+            text: text.into(),
+        }
+    }
+}
+
+/// *lexicon* element referencing custom lexicon, must precede other content of *Voice*.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lexicon {
+    uri: String,
+}
+
+impl Lexicon {
+    pub fn new(uri: impl Into<String>) -> Self {
+        Lexicon { uri: uri.into() }
+    }
+}
+
+/// *bookmark* element, reported by *SpeechSynthesizer* bookmark reached event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    mark: String,
+}
+
+impl Bookmark {
+    pub fn new(mark: impl Into<String>) -> Self {
+        Bookmark { mark: mark.into() }
+    }
+}
+
+/// *audio* element inserting prerecorded audio, its content is spoken when audio is unavailable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Audio {
+    src: String,
+    children: Vec<Node>,
+}
+
+impl Audio {
+    pub fn new(src: impl Into<String>) -> Self {
+        Audio {
+            src: src.into(),
+            children: vec![],
+        }
+    }
+}
+
+/// Role played by voice in *ExpressAs*.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Girl,
+    Boy,
+    YoungAdultFemale,
+    YoungAdultMale,
+    OlderAdultFemale,
+    OlderAdultMale,
+    SeniorFemale,
+    SeniorMale,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Girl => "Girl",
+            Role::Boy => "Boy",
+            Role::YoungAdultFemale => "YoungAdultFemale",
+            Role::YoungAdultMale => "YoungAdultMale",
+            Role::OlderAdultFemale => "OlderAdultFemale",
+            Role::OlderAdultMale => "OlderAdultMale",
+            Role::SeniorFemale => "SeniorFemale",
+            Role::SeniorMale => "SeniorMale",
+        }
+    }
+}
+
+/// *mstts:express-as* element setting speaking style (e.g. *cheerful*) and role of the voice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpressAs {
+    style: String,
+    style_degree: Option<f32>,
+    role: Option<Role>,
+    children: Vec<Node>,
+}
+
+impl ExpressAs {
+    pub fn new(style: impl Into<String>) -> Self {
+        ExpressAs {
+            style: style.into(),
+            style_degree: None,
+            role: None,
+            children: vec![],
+        }
+    }
+
+    /// Intensity of the style in range 0.01 - 2.0, default is 1.0.
+    pub fn style_degree(mut self, style_degree: f32) -> Self {
+        self.style_degree = Some(style_degree);
+        self
+    }
+
+    pub fn role(mut self, role: Role) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    pub fn get_style(&self) -> &str {
+        &self.style
+    }
+
+    pub fn get_role(&self) -> Option<Role> {
+        self.role
+    }
+}
+
+macro_rules! impl_node {
+    ($variant:ident) => {
+        impl From<$variant> for Node {
+            fn from(element: $variant) -> Self {
+                Node::$variant(element)
+            }
+        }
+    };
+}
+
+macro_rules! impl_container {
+    ($container:ident) => {
+        impl $container {
+            /// Appends text, it is escaped when rendered.
+            pub fn text(mut self, text: impl Into<String>) -> Self {
+                self.children.push(Node::Text(text.into()));
+                self
+            }
+
+            /// Appends child element.
+            pub fn child(mut self, node: impl Into<Node>) -> Self {
+                self.children.push(node.into());
+                self
+            }
+        }
+    };
+}
+
+impl_node!(Lang);
+impl_node!(Prosody);
+impl_node!(Break);
+impl_node!(Emphasis);
+impl_node!(SayAs);
+impl_node!(Phoneme);
+impl_node!(Lexicon);
+impl_node!(Bookmark);
+impl_node!(Audio);
+impl_node!(ExpressAs);
+
+impl_container!(Voice);
+impl_container!(Lang);
+impl_container!(Prosody);
+impl_container!(Emphasis);
+impl_container!(Audio);
+impl_container!(ExpressAs);
+
+impl From<String> for Node {
+    fn from(text: String) -> Self {
+        Node::Text(text)
+    }
+}
+
+impl From<&str> for Node {
+    fn from(text: &str) -> Self {
+        Node::Text(text.to_owned())
+    }
+}
+
+fn validate_children(children: &[Node], in_express_as: bool) -> Result<()> {
+    children
+        .iter()
+        .try_for_each(|node| node.validate(in_express_as, false))
+}
+
+fn invalid(message: String) -> Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message).into()
+}
+
+/// Rejects characters which cannot be represented in XML 1.0 even when escaped.
+fn check_chars(what: &str, value: &str) -> Result<()> {
+    match value.chars().find(|c| {
+        (*c < ' ' && !matches!(c, '\t' | '\n' | '\r')) || matches!(c, '\u{FFFE}' | '\u{FFFF}')
+    }) {
+        Some(c) => Err(invalid(format!(
+            "{} contains character {:?} not allowed in SSML",
+            what, c
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn check_not_empty(element: &str, attribute: &str, value: &str) -> Result<()> {
+    if value.trim().is_empty() {
+        return Err(invalid(format!(
+            "{} {} must not be empty",
+            element, attribute
+        )));
+    }
+    check_chars(element, value)
+}
+
+/// Checks prosody value is keyword or (signed) number followed by one of *units*.
+fn check_prosody_value(
+    attribute: &str,
+    value: &str,
+    keywords: &[&str],
+    units: &[&str],
+) -> Result<()> {
+    if keywords.contains(&value) {
+        return Ok(());
+    }
+    let unsigned = value.strip_prefix(['+', '-']).unwrap_or(value);
+    let valid = units.iter().any(|unit| {
+        unsigned
+            .strip_suffix(unit)
+            .and_then(|number| number.parse::<f64>().ok())
+            .is_some_and(|number| number.is_finite() && number >= 0.0)
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(invalid(format!(
+            "prosody {} {:?} is not valid, expected one of {:?} or number with unit {:?}",
+            attribute, value, keywords, units
+        )))
+    }
+}
+
+fn push_escaped(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn push_attribute(out: &mut String, name: &str, value: &str) {
+    out.push(' ');
+    out.push_str(name);
+    out.push_str("=\"");
+    push_escaped(out, value);
+    out.push('"');
+}
+
+fn push_start_tag(out: &mut String, name: &str, attributes: &[(&str, Option<&str>)]) {
+    out.push('<');
+    out.push_str(name);
+    for (attribute, value) in attributes {
+        if let Some(value) = value {
+            push_attribute(out, attribute, value);
+        }
+    }
+}
+
+fn render_element(
+    out: &mut String,
+    name: &str,
+    attributes: &[(&str, Option<&str>)],
+    children: &[Node],
+) {
+    push_start_tag(out, name, attributes);
+    if children.is_empty() {
+        out.push_str("/>");
+        return;
+    }
+    out.push('>');
+    for child in children {
+        child.render(out);
+    }
+    out.push_str("</");
+    out.push_str(name);
+    out.push('>');
+}
+
+fn render_text_element(
+    out: &mut String,
+    name: &str,
+    attributes: &[(&str, Option<&str>)],
+    text: &str,
+) {
+    push_start_tag(out, name, attributes);
+    out.push('>');
+    push_escaped(out, text);
+    out.push_str("</");
+    out.push_str(name);
+    out.push('>');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_escaped_document() {
+        let ssml = Speak::new("en-US")
+            .voice(
+                Voice::new("en-US-AvaNeural")
+                    .child(Lexicon::new("https://example.com/lexicon.xml?a=1&b=2"))
+                    .text("Tom & Jerry say <hi> ")
+                    .child(Break::Time(Duration::from_millis(300)))
+                    .child(
+                        ExpressAs::new("cheerful")
+                            .role(Role::Girl)
+                            .style_degree(1.5)
+                            .child(Prosody::new().rate("+10%").pitch("-2st").text("fast")),
+                    )
+                    .child(SayAs::new("cardinal", "42"))
+                    .child(Bookmark::new("end")),
+            )
+            .to_ssml()
+            .unwrap();
+        assert_eq!(
+            ssml,
+            "<speak version=\"1.0\" xmlns=\"http://www.w3.org/2001/10/synthesis\" \
+             xmlns:mstts=\"https://www.w3.org/2001/mstts\" xml:lang=\"en-US\">\
+             <voice name=\"en-US-AvaNeural\">\
+             <lexicon uri=\"https://example.com/lexicon.xml?a=1&amp;b=2\"/>\
+             Tom &amp; Jerry say &lt;hi&gt; <break time=\"300ms\"/>\
+             <mstts:express-as style=\"cheerful\" styledegree=\"1.5\" role=\"Girl\">\
+             <prosody rate=\"+10%\" pitch=\"-2st\">fast</prosody></mstts:express-as>\
+             <say-as interpret-as=\"cardinal\">42</say-as><bookmark mark=\"end\"/>\
+             </voice></speak>"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_documents() {
+        let voice = || Voice::new("en-US-AvaNeural");
+        let invalid_documents = vec![
+            Speak::new("en-US"),
+            Speak::new("en-US").voice(Voice::new(" ")),
+            Speak::new("en-US").voice(voice().text("bell \u{7}")),
+            Speak::new("en-US").voice(voice().child(Prosody::new())),
+            Speak::new("en-US").voice(voice().child(Prosody::new().rate("fastest"))),
+            Speak::new("en-US").voice(voice().child(Prosody::new().pitch("+5dB"))),
+            Speak::new("en-US").voice(voice().child(Break::Time(Duration::from_secs(30)))),
+            Speak::new("en-US").voice(voice().child(ExpressAs::new("sad").style_degree(3.0))),
+            Speak::new("en-US")
+                .voice(voice().child(ExpressAs::new("sad").child(ExpressAs::new("angry")))),
+            Speak::new("en-US").voice(voice().text("hi").child(Lexicon::new("uri"))),
+            Speak::new("en-US").voice(voice().child(Emphasis::new().child(Lexicon::new("uri")))),
+        ];
+        for document in invalid_documents {
+            assert!(
+                document.to_ssml().is_err(),
+                "{:?} should be invalid",
+                document
+            );
+        }
+        assert!(Speak::new("en-US")
+            .voice(voice().child(Prosody::new().rate("1.5").volume("loud").pitch("+50Hz")))
+            .to_ssml()
+            .is_ok());
+    }
+}
@@ -0,0 +1,291 @@
+use crate::error::Result;
+use crate::speech::ssml::{Node, Speak};
+use crate::speech::{CatalogVoice, SynthesisVoicesResult, VoiceInfo};
+use std::fmt;
+use std::io;
+
+/// VoiceCapabilities describes what SSML features the voice supports,
+/// plain data counterpart of *VoiceInfo* used by *SsmlVoiceChecker*.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoiceCapabilities {
+    pub name: String,
+    pub short_name: String,
+    pub locale: String,
+    pub styles: Vec<String>,
+    /// *None* when the catalog does not report roles of the voice.
+    pub roles: Option<Vec<String>>,
+    pub secondary_locales: Vec<String>,
+}
+
+impl VoiceCapabilities {
+    /// Multilingual voices can speak any supported locale selected by *lang* element.
+    pub fn is_multilingual(&self) -> bool {
+        self.short_name.contains("Multilingual") || !self.secondary_locales.is_empty()
+    }
+
+    pub fn supports_locale(&self, locale: &str) -> bool {
+        self.locale.eq_ignore_ascii_case(locale)
+            || self
+                .secondary_locales
+                .iter()
+                .any(|secondary| secondary.eq_ignore_ascii_case(locale))
+            || self.short_name.contains("Multilingual")
+    }
+
+    pub fn supports_style(&self, style: &str) -> bool {
+        self.styles.iter().any(|s| s.eq_ignore_ascii_case(style))
+    }
+
+    /// Returns *true* also when roles of the voice are not known.
+    pub fn supports_role(&self, role: &str) -> bool {
+        match &self.roles {
+            Some(roles) => roles.iter().any(|r| r.eq_ignore_ascii_case(role)),
+            None => true,
+        }
+    }
+
+    fn matches_name(&self, name: &str) -> bool {
+        self.short_name.eq_ignore_ascii_case(name) || self.name.eq_ignore_ascii_case(name)
+    }
+}
+
+impl From<&CatalogVoice> for VoiceCapabilities {
+    fn from(voice: &CatalogVoice) -> Self {
+        VoiceCapabilities {
+            name: voice.name.clone(),
+            short_name: voice.short_name.clone(),
+            locale: voice.locale.clone(),
+            styles: voice.styles.clone(),
+            roles: voice.roles.clone(),
+            secondary_locales: voice.secondary_locales.clone(),
+        }
+    }
+}
+
+impl From<&VoiceInfo> for VoiceCapabilities {
+    fn from(voice: &VoiceInfo) -> Self {
+        VoiceCapabilities::from(&CatalogVoice::from(voice))
+    }
+}
+
+/// Problem found by *SsmlVoiceChecker*. The service silently ignores most of these
+/// (e.g. unsupported style), so they are reported before the synthesis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SsmlIssue {
+    UnknownVoice {
+        voice: String,
+    },
+    UnsupportedStyle {
+        voice: String,
+        style: String,
+        supported: Vec<String>,
+    },
+    UnsupportedRole {
+        voice: String,
+        role: String,
+    },
+    /// Language of *lang* element is not spoken by the voice.
+    LocaleMismatch {
+        voice: String,
+        voice_locale: String,
+        lang: String,
+    },
+}
+
+impl fmt::Display for SsmlIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SsmlIssue::UnknownVoice { voice } => write!(f, "unknown voice {}", voice),
+            SsmlIssue::UnsupportedStyle {
+                voice,
+                style,
+                supported,
+            } => write!(
+                f,
+                "voice {} does not support style {} (supported: {})",
+                voice,
+                style,
+                supported.join(", ")
+            ),
+            SsmlIssue::UnsupportedRole { voice, role } => {
+                write!(f, "voice {} does not support role {}", voice, role)
+            }
+            SsmlIssue::LocaleMismatch {
+                voice,
+                voice_locale,
+                lang,
+            } => write!(
+                f,
+                "voice {} of locale {} cannot speak {}",
+                voice, voice_locale, lang
+            ),
+        }
+    }
+}
+
+/// SsmlVoiceChecker checks SSML document against voice catalog returned by
+/// *SpeechSynthesizer::get_voices_async*: reports unknown voices, *mstts:express-as*
+/// styles and roles not supported by the voice and *lang* elements in locales
+/// the voice does not speak.
+#[derive(Debug, Clone)]
+pub struct SsmlVoiceChecker {
+    voices: Vec<VoiceCapabilities>,
+}
+
+impl SsmlVoiceChecker {
+    pub fn new(voices: Vec<VoiceCapabilities>) -> Self {
+        SsmlVoiceChecker { voices }
+    }
+
+    pub fn from_voices_result(result: &SynthesisVoicesResult) -> Self {
+        SsmlVoiceChecker::new(result.voices.iter().map(VoiceCapabilities::from).collect())
+    }
+
+    /// Returns all issues found in the document, empty when the document is fine.
+    pub fn check(&self, speak: &Speak) -> Vec<SsmlIssue> {
+        let mut issues = vec![];
+        for voice in speak.get_voices() {
+            let name = voice.get_name();
+            let capabilities = match self.voices.iter().find(|v| v.matches_name(name)) {
+                Some(capabilities) => capabilities,
+                None => {
+                    issues.push(SsmlIssue::UnknownVoice {
+                        voice: name.to_owned(),
+                    });
+                    continue;
+                }
+            };
+            // root xml:lang does not affect the voice, only explicit lang elements do
+            check_nodes(capabilities, name, voice.get_children(), &mut issues);
+        }
+        issues
+    }
+
+    /// Same as *check*, returns error describing all issues found.
+    pub fn validate(&self, speak: &Speak) -> Result<()> {
+        let issues = self.check(speak);
+        if issues.is_empty() {
+            return Ok(());
+        }
+        let message = issues
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(io::Error::new(io::ErrorKind::InvalidInput, message).into())
+    }
+}
+
+fn check_locale(
+    capabilities: &VoiceCapabilities,
+    voice: &str,
+    lang: &str,
+    issues: &mut Vec<SsmlIssue>,
+) {
+    if !capabilities.supports_locale(lang) {
+        issues.push(SsmlIssue::LocaleMismatch {
+            voice: voice.to_owned(),
+            voice_locale: capabilities.locale.clone(),
+            lang: lang.to_owned(),
+        });
+    }
+}
+
+fn check_nodes(
+    capabilities: &VoiceCapabilities,
+    voice: &str,
+    nodes: &[Node],
+    issues: &mut Vec<SsmlIssue>,
+) {
+    for node in nodes {
+        match node {
+            Node::ExpressAs(express_as) => {
+                if !capabilities.supports_style(express_as.get_style()) {
+                    issues.push(SsmlIssue::UnsupportedStyle {
+                        voice: voice.to_owned(),
+                        style: express_as.get_style().to_owned(),
+                        supported: capabilities.styles.clone(),
+                    });
+                }
+                if let Some(role) = express_as.get_role() {
+                    if !capabilities.supports_role(role.as_str()) {
+                        issues.push(SsmlIssue::UnsupportedRole {
+                            voice: voice.to_owned(),
+                            role: role.as_str().to_owned(),
+                        });
+                    }
+                }
+            }
+            Node::Lang(lang) => check_locale(capabilities, voice, lang.get_lang(), issues),
+            _ => {}
+        }
+        check_nodes(capabilities, voice, node.get_children(), issues);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speech::ssml::{ExpressAs, Lang, Role, Voice};
+
+    fn checker() -> SsmlVoiceChecker {
+        SsmlVoiceChecker::new(vec![
+            VoiceCapabilities {
+                name: "Microsoft Server Speech Text to Speech Voice (en-US, JennyNeural)".into(),
+                short_name: "en-US-JennyNeural".into(),
+                locale: "en-US".into(),
+                styles: vec!["cheerful".into(), "sad".into()],
+                roles: None,
+                secondary_locales: vec![],
+            },
+            VoiceCapabilities {
+                short_name: "zh-CN-XiaomoNeural".into(),
+                locale: "zh-CN".into(),
+                styles: vec!["calm".into()],
+                roles: Some(vec!["Girl".into(), "Boy".into()]),
+                ..Default::default()
+            },
+        ])
+    }
+
+    #[test]
+    fn reports_unsupported_voices_styles_and_locales() {
+        let speak = Speak::new("en-US")
+            .voice(
+                Voice::new("en-US-JennyNeural")
+                    .child(ExpressAs::new("Cheerful").text("fine"))
+                    .child(ExpressAs::new("angry").text("unsupported style"))
+                    .child(Lang::new("de-DE").text("Hallo")),
+            )
+            .voice(
+                Voice::new("zh-CN-XiaomoNeural")
+                    .child(ExpressAs::new("calm").role(Role::SeniorMale).text("hi")),
+            )
+            .voice(Voice::new("en-US-Unknown").text("hi"));
+        assert_eq!(
+            checker().check(&speak),
+            vec![
+                SsmlIssue::UnsupportedStyle {
+                    voice: "en-US-JennyNeural".into(),
+                    style: "angry".into(),
+                    supported: vec!["cheerful".into(), "sad".into()],
+                },
+                SsmlIssue::LocaleMismatch {
+                    voice: "en-US-JennyNeural".into(),
+                    voice_locale: "en-US".into(),
+                    lang: "de-DE".into(),
+                },
+                SsmlIssue::UnsupportedRole {
+                    voice: "zh-CN-XiaomoNeural".into(),
+                    role: "SeniorMale".into(),
+                },
+                SsmlIssue::UnknownVoice {
+                    voice: "en-US-Unknown".into(),
+                },
+            ]
+        );
+        assert!(checker()
+            .validate(&Speak::new("en-us").voice(Voice::new("en-US-JennyNeural").text("hi")))
+            .is_ok());
+    }
+}
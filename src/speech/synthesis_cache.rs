@@ -0,0 +1,631 @@
+use crate::common::{
+    PropertyId, ResultReason, SpeechSynthesisBoundaryType, SpeechSynthesisOutputFormat,
+};
+use crate::error::{Error, ErrorRootCause, Result};
+use crate::speech::synthesis_event_collector::EventCollector;
+use crate::speech::{
+    SpeechSynthesisResult, SpeechSynthesizer, SynthesisBookmark, SynthesisWordBoundary, Viseme,
+    VisemeFrame, VisemeTimeline,
+};
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Leading bytes of cache entry file, the last byte is format version.
+const ENTRY_MAGIC: &[u8; 4] = b"SXC\x01";
+const ENTRY_EXTENSION: &str = "bin";
+
+/// Makes names of temporary entry files unique among concurrent inserts of the process.
+static TMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Key of *SynthesisCache* entry. Hash of the key is used as file name,
+/// whole key is stored in the entry to detect hash collisions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SynthesisCacheKey {
+    key: String,
+    hash: u64,
+}
+
+impl SynthesisCacheKey {
+    pub fn text(voice: &str, format: SpeechSynthesisOutputFormat, text: &str) -> Self {
+        SynthesisCacheKey::new("text", voice, format, text.trim())
+    }
+
+    /// SSML is normalized first, so that documents differing only in formatting share the entry.
+    pub fn ssml(voice: &str, format: SpeechSynthesisOutputFormat, ssml: &str) -> Self {
+        SynthesisCacheKey::new("ssml", voice, format, &normalize_ssml(ssml))
+    }
+
+    fn new(kind: &str, voice: &str, format: SpeechSynthesisOutputFormat, input: &str) -> Self {
+        let key = format!("{}\n{}\n{}\n{}", kind, voice, format.name(), input);
+        let hash = fnv1a(key.as_bytes());
+        SynthesisCacheKey { key, hash }
+    }
+
+    fn file_name(&self) -> String {
+        format!("{:016x}.{}", self.hash, ENTRY_EXTENSION)
+    }
+}
+
+/// Elements whose content is not spoken text, whitespace between two of them is insignificant.
+const STRUCTURAL_TAGS: &[&str] = &[
+    "speak",
+    "voice",
+    "p",
+    "s",
+    "lexicon",
+    "mstts:backgroundaudio",
+    "mstts:silence",
+    "mstts:viseme",
+];
+
+/// Collapses whitespace runs into single space. Whitespace between two structural tags
+/// (e.g. *</voice>* and *</speak>*) is dropped, whitespace between other tags is kept
+/// as it separates spoken words (e.g. *<emphasis>a</emphasis> <emphasis>b</emphasis>*).
+fn normalize_ssml(ssml: &str) -> String {
+    let ssml = ssml.trim();
+    let mut normalized = String::with_capacity(ssml.len());
+    let mut pending_space = false;
+    for (position, c) in ssml.char_indices() {
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if pending_space {
+            let previous_tag = normalized
+                .strip_suffix('>')
+                .and_then(|text| text.rfind('<').map(|start| &text[start + 1..]));
+            let between_structural_tags = c == '<'
+                && previous_tag.is_some_and(is_structural_tag)
+                && is_structural_tag(&ssml[position + 1..]);
+            if !between_structural_tags {
+                normalized.push(' ');
+            }
+        }
+        pending_space = false;
+        normalized.push(c);
+    }
+    normalized
+}
+
+/// *tag* is the text following *<*.
+fn is_structural_tag(tag: &str) -> bool {
+    let name = tag
+        .trim_start_matches('/')
+        .split(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .next()
+        .unwrap_or_default();
+    STRUCTURAL_TAGS.contains(&name)
+}
+
+/// 64-bit FNV-1a, stable across Rust versions unlike *DefaultHasher*.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Audio and event metadata of one synthesis as stored in *SynthesisCache*.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CachedSynthesisResult {
+    pub audio_data: Vec<u8>,
+    pub audio_duration: Duration,
+    pub word_boundaries: Vec<SynthesisWordBoundary>,
+    pub visemes: VisemeTimeline,
+    pub bookmarks: Vec<SynthesisBookmark>,
+    /// *true* when the result was read from the cache instead of synthesized.
+    pub cache_hit: bool,
+}
+
+/// SynthesisCache stores synthesis results in a directory, one file per entry.
+/// When total size of entries exceeds *max_bytes*, least recently used entries are evicted.
+#[derive(Debug, Clone)]
+pub struct SynthesisCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl SynthesisCache {
+    /// Opens the cache, creating *dir* when it does not exist.
+    pub fn new<P: AsRef<Path>>(dir: P, max_bytes: u64) -> Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(SynthesisCache {
+            dir: dir.as_ref().to_path_buf(),
+            max_bytes,
+        })
+    }
+
+    /// Returns the entry for the key, *None* when missing or unreadable.
+    pub fn get(&self, key: &SynthesisCacheKey) -> Option<CachedSynthesisResult> {
+        let path = self.dir.join(key.file_name());
+        let bytes = fs::read(&path).ok()?;
+        let (entry_key, mut result) = decode_entry(&bytes).ok()?;
+        if entry_key != key.key {
+            return None;
+        }
+        // modification time tracks the last use for eviction
+        if let Ok(file) = File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        result.cache_hit = true;
+        Some(result)
+    }
+
+    /// Stores the entry and evicts least recently used entries over the size limit.
+    pub fn insert(&self, key: &SynthesisCacheKey, result: &CachedSynthesisResult) -> Result<()> {
+        let bytes = encode_entry(&key.key, result);
+        if bytes.len() as u64 > self.max_bytes {
+            return Ok(());
+        }
+        let path = self.dir.join(key.file_name());
+        let tmp_path = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            TMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp_path, &bytes)?;
+        fs::rename(&tmp_path, &path)?;
+        self.evict(self.max_bytes)
+    }
+
+    pub fn remove(&self, key: &SynthesisCacheKey) -> Result<()> {
+        match fs::remove_file(self.dir.join(key.file_name())) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        self.evict(0)
+    }
+
+    /// Total size of entries in bytes.
+    pub fn size(&self) -> Result<u64> {
+        Ok(self.entries()?.iter().map(|(_, len, _)| len).sum())
+    }
+
+    /// Removes least recently used entries until their total size is at most *max_bytes*.
+    pub fn evict(&self, max_bytes: u64) -> Result<()> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in entries {
+            if total <= max_bytes {
+                break;
+            }
+            match fs::remove_file(&path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+                _ => total -= len,
+            }
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut entries = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((path, metadata.len(), modified));
+        }
+        Ok(entries)
+    }
+}
+
+/// Events of the synthesis in progress collected from synthesizer callbacks.
+#[derive(Debug, Default)]
+struct CollectedEvents {
+    word_boundaries: Vec<SynthesisWordBoundary>,
+    visemes: VisemeTimeline,
+    viseme_error: Option<String>,
+    bookmarks: Vec<SynthesisBookmark>,
+}
+
+/// CachedSpeechSynthesizer serves *speak_text_async* / *speak_ssml_async* from
+/// *SynthesisCache* and synthesizes (and stores) only inputs not seen before.
+/// It takes over word boundary, viseme and bookmark callbacks of the synthesizer
+/// to store their events along with the audio. Speak methods take *&mut self*
+/// so that events of concurrent syntheses cannot be mixed.
+pub struct CachedSpeechSynthesizer {
+    // boxed so that synthesizer does not move after its callbacks are set
+    synthesizer: Box<SpeechSynthesizer>,
+    cache: SynthesisCache,
+    voice: String,
+    format: SpeechSynthesisOutputFormat,
+    events: EventCollector<CollectedEvents>,
+}
+
+impl CachedSpeechSynthesizer {
+    /// *voice* and *format* must match the configuration of *synthesizer*, they are part of cache key.
+    pub fn new(
+        synthesizer: SpeechSynthesizer,
+        cache: SynthesisCache,
+        voice: &str,
+        format: SpeechSynthesisOutputFormat,
+    ) -> Result<Self> {
+        let mut synthesizer = Box::new(synthesizer);
+        let events = EventCollector::<CollectedEvents>::default();
+
+        let word_boundary_events = events.clone();
+        synthesizer.set_synthesizer_word_boundary_cb(move |event| {
+            word_boundary_events
+                .lock()
+                .word_boundaries
+                .push((&event).into());
+        })?;
+        let viseme_events = events.clone();
+        synthesizer.set_synthesizer_viseme_cb(move |event| {
+            let mut events = viseme_events.lock();
+            if let Err(err) = events.visemes.push_event(&event) {
+                events.viseme_error.get_or_insert_with(|| err.to_string());
+            }
+        })?;
+        let bookmark_events = events.clone();
+        synthesizer.set_synthesizer_bookmark_cb(move |event| {
+            bookmark_events.lock().bookmarks.push((&event).into());
+        })?;
+
+        Ok(CachedSpeechSynthesizer {
+            synthesizer,
+            cache,
+            voice: voice.to_owned(),
+            format,
+            events,
+        })
+    }
+
+    pub fn synthesizer(&self) -> &SpeechSynthesizer {
+        &self.synthesizer
+    }
+
+    pub fn cache(&self) -> &SynthesisCache {
+        &self.cache
+    }
+
+    pub async fn speak_text_async(&mut self, text: &str) -> Result<CachedSynthesisResult> {
+        let key = SynthesisCacheKey::text(&self.voice, self.format, text);
+        if let Some(result) = self.cache.get(&key) {
+            return Ok(result);
+        }
+        // discard events left by a synthesis which failed before *store*
+        self.events.take();
+        let result = self.synthesizer.speak_text_async(text).await?;
+        self.store(&key, result)
+    }
+
+    pub async fn speak_ssml_async(&mut self, ssml: &str) -> Result<CachedSynthesisResult> {
+        let key = SynthesisCacheKey::ssml(&self.voice, self.format, ssml);
+        if let Some(result) = self.cache.get(&key) {
+            return Ok(result);
+        }
+        self.events.take();
+        let result = self.synthesizer.speak_ssml_async(ssml).await?;
+        self.store(&key, result)
+    }
+
+    fn store(
+        &self,
+        key: &SynthesisCacheKey,
+        result: SpeechSynthesisResult,
+    ) -> Result<CachedSynthesisResult> {
+        if let ResultReason::Canceled = result.reason {
+            let details = result
+                .properties
+                .get_property(PropertyId::CancellationDetailsReasonDetailedText, "")?;
+            return Err(Error::new(
+                format!("synthesis canceled: {}", details),
+                ErrorRootCause::ApiError(0x032),
+            ));
+        }
+        let events = self.events.take();
+        if let Some(error) = events.viseme_error {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error).into());
+        }
+        let result = CachedSynthesisResult {
+            audio_data: result.audio_data,
+            audio_duration: Duration::from_millis(result.audio_duration_ms),
+            word_boundaries: events.word_boundaries,
+            visemes: events.visemes,
+            bookmarks: events.bookmarks,
+            cache_hit: false,
+        };
+        self.cache.insert(key, &result)?;
+        Ok(result)
+    }
+}
+
+fn encode_entry(key: &str, result: &CachedSynthesisResult) -> Vec<u8> {
+    let mut out = Vec::with_capacity(result.audio_data.len() + 1024);
+    out.extend_from_slice(ENTRY_MAGIC);
+    put_bytes(&mut out, key.as_bytes());
+    put_u64(&mut out, result.audio_duration.as_nanos() as u64);
+
+    put_u64(&mut out, result.word_boundaries.len() as u64);
+    for boundary in &result.word_boundaries {
+        put_u64(&mut out, boundary.audio_offset);
+        put_u64(&mut out, boundary.duration);
+        // u64::MAX marks unknown text offset
+        put_u64(&mut out, boundary.text_offset.map_or(u64::MAX, u64::from));
+        put_u64(&mut out, u64::from(boundary.word_length));
+        put_u64(&mut out, boundary.boundary_type as u64);
+        put_bytes(&mut out, boundary.text.as_bytes());
+    }
+
+    put_u64(&mut out, result.visemes.visemes.len() as u64);
+    for viseme in &result.visemes.visemes {
+        put_u64(&mut out, viseme.audio_offset);
+        put_u64(&mut out, u64::from(viseme.viseme_id));
+    }
+    put_u64(&mut out, result.visemes.frames.len() as u64);
+    for frame in &result.visemes.frames {
+        put_u64(&mut out, frame.index);
+        put_u64(&mut out, frame.audio_offset);
+        put_u64(&mut out, frame.blend_shapes.len() as u64);
+        for weight in &frame.blend_shapes {
+            out.extend_from_slice(&weight.to_le_bytes());
+        }
+    }
+
+    put_u64(&mut out, result.bookmarks.len() as u64);
+    for bookmark in &result.bookmarks {
+        put_u64(&mut out, bookmark.audio_offset);
+        put_bytes(&mut out, bookmark.text.as_bytes());
+    }
+
+    put_bytes(&mut out, &result.audio_data);
+    out
+}
+
+fn decode_entry(bytes: &[u8]) -> Result<(String, CachedSynthesisResult)> {
+    let mut reader = EntryReader { rest: bytes };
+    if reader.take(ENTRY_MAGIC.len())? != ENTRY_MAGIC {
+        return Err(invalid_entry());
+    }
+    let key = reader.string()?;
+    let audio_duration = Duration::from_nanos(reader.u64()?);
+
+    let mut word_boundaries = vec![];
+    for _ in 0..reader.len()? {
+        let audio_offset = reader.u64()?;
+        let duration = reader.u64()?;
+        let text_offset = match reader.u64()? {
+            u64::MAX => None,
+            offset => Some(reader.u32_from(offset)?),
+        };
+        let word_length = reader.u32()?;
+        let boundary_type = SpeechSynthesisBoundaryType::from_u32(reader.u32()?);
+        word_boundaries.push(SynthesisWordBoundary {
+            audio_offset,
+            duration,
+            text_offset,
+            word_length,
+            boundary_type,
+            text: reader.string()?,
+        });
+    }
+
+    let mut visemes = VisemeTimeline::new();
+    for _ in 0..reader.len()? {
+        visemes.visemes.push(Viseme {
+            audio_offset: reader.u64()?,
+            viseme_id: reader.u32()?,
+        });
+    }
+    for _ in 0..reader.len()? {
+        let index = reader.u64()?;
+        let audio_offset = reader.u64()?;
+        let mut blend_shapes = vec![];
+        for _ in 0..reader.len()? {
+            let weight = reader.take(4)?;
+            blend_shapes.push(f32::from_le_bytes([
+                weight[0], weight[1], weight[2], weight[3],
+            ]));
+        }
+        visemes.frames.push(VisemeFrame {
+            index,
+            audio_offset,
+            blend_shapes,
+        });
+    }
+
+    let mut bookmarks = vec![];
+    for _ in 0..reader.len()? {
+        bookmarks.push(SynthesisBookmark {
+            audio_offset: reader.u64()?,
+            text: reader.string()?,
+        });
+    }
+
+    let audio_data = reader.bytes()?.to_vec();
+    if !reader.rest.is_empty() {
+        return Err(invalid_entry());
+    }
+    Ok((
+        key,
+        CachedSynthesisResult {
+            audio_data,
+            audio_duration,
+            word_boundaries,
+            visemes,
+            bookmarks,
+            cache_hit: false,
+        },
+    ))
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    put_u64(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn invalid_entry() -> Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid synthesis cache entry").into()
+}
+
+struct EntryReader<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> EntryReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.rest.len() < len {
+            return Err(invalid_entry());
+        }
+        let (taken, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Ok(taken)
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let mut value = [0u8; 8];
+        value.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(value))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let value = self.u64()?;
+        self.u32_from(value)
+    }
+
+    fn u32_from(&self, value: u64) -> Result<u32> {
+        u32::try_from(value).map_err(|_| invalid_entry())
+    }
+
+    /// Reads item count, bounded by remaining bytes so that corrupted entry cannot allocate much.
+    fn len(&mut self) -> Result<usize> {
+        let len = self.u64()?;
+        if len > self.rest.len() as u64 {
+            return Err(invalid_entry());
+        }
+        Ok(len as usize)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| invalid_entry())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(audio_len: usize) -> CachedSynthesisResult {
+        CachedSynthesisResult {
+            audio_data: vec![7; audio_len],
+            audio_duration: Duration::from_millis(1200),
+            word_boundaries: vec![SynthesisWordBoundary {
+                audio_offset: 500_000,
+                duration: 2_000_000,
+                text_offset: None,
+                word_length: 5,
+                boundary_type: SpeechSynthesisBoundaryType::WordBoundary,
+                text: "hello".into(),
+            }],
+            visemes: VisemeTimeline {
+                visemes: vec![Viseme {
+                    audio_offset: 100,
+                    viseme_id: 21,
+                }],
+                frames: vec![VisemeFrame {
+                    index: 1,
+                    audio_offset: 166_666,
+                    blend_shapes: vec![0.25; 55],
+                }],
+            },
+            bookmarks: vec![SynthesisBookmark {
+                audio_offset: 900,
+                text: "mark".into(),
+            }],
+            cache_hit: false,
+        }
+    }
+
+    #[test]
+    fn keys_normalize_ssml() {
+        let format = SpeechSynthesisOutputFormat::Riff16Khz16BitMonoPcm;
+        assert_eq!(
+            SynthesisCacheKey::ssml(
+                "en-US-JennyNeural",
+                format,
+                "<speak>\n  <voice>Hello   world</voice>\n</speak>"
+            ),
+            SynthesisCacheKey::ssml(
+                "en-US-JennyNeural",
+                format,
+                "<speak><voice>Hello world</voice></speak>"
+            )
+        );
+        assert_ne!(
+            SynthesisCacheKey::ssml(
+                "en-US-JennyNeural",
+                format,
+                "<speak><voice><emphasis>a</emphasis> <emphasis>b</emphasis></voice></speak>"
+            ),
+            SynthesisCacheKey::ssml(
+                "en-US-JennyNeural",
+                format,
+                "<speak><voice><emphasis>a</emphasis><emphasis>b</emphasis></voice></speak>"
+            )
+        );
+        assert_ne!(
+            SynthesisCacheKey::text("en-US-JennyNeural", format, "Hello"),
+            SynthesisCacheKey::text("en-US-GuyNeural", format, "Hello")
+        );
+    }
+
+    #[test]
+    fn stores_and_evicts_entries() {
+        let dir = std::env::temp_dir().join(format!("synthesis_cache_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = SynthesisCache::new(&dir, 3000).unwrap();
+        let format = SpeechSynthesisOutputFormat::Audio16Khz32KBitRateMonoMp3;
+        let first = SynthesisCacheKey::text("voice", format, "first");
+        let second = SynthesisCacheKey::text("voice", format, "second");
+
+        cache.insert(&first, &result(1000)).unwrap();
+        let cached = cache.get(&first).unwrap();
+        assert!(cached.cache_hit);
+        assert_eq!(
+            CachedSynthesisResult {
+                cache_hit: false,
+                ..cached
+            },
+            result(1000)
+        );
+        assert!(cache.get(&second).is_none());
+
+        // older entry has to go once the limit is exceeded
+        let old = SystemTime::now() - Duration::from_secs(60);
+        File::options()
+            .write(true)
+            .open(dir.join(first.file_name()))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        cache.insert(&second, &result(1500)).unwrap();
+        assert!(cache.get(&first).is_none());
+        assert!(cache.get(&second).is_some());
+        assert!(cache.size().unwrap() <= 3000);
+
+        cache.clear().unwrap();
+        assert_eq!(cache.size().unwrap(), 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,355 @@
+use crate::common::SpeechSynthesisBoundaryType;
+use crate::error::Result;
+use crate::speech::synthesis_event_collector::EventCollector;
+use crate::speech::{SpeechSynthesizer, SynthesisWordBoundary};
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Configuration of *build_captions*.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptionConfig {
+    pub max_chars_per_line: usize,
+    pub max_lines_per_cue: usize,
+    /// Short cues are prolonged up to this duration unless they would overlap the next cue.
+    pub min_cue_duration: Duration,
+}
+
+impl Default for CaptionConfig {
+    fn default() -> Self {
+        CaptionConfig {
+            max_chars_per_line: 42,
+            max_lines_per_cue: 2,
+            min_cue_duration: Duration::from_millis(1000),
+        }
+    }
+}
+
+/// Single subtitle cue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Caption {
+    pub start: Duration,
+    pub end: Duration,
+    pub lines: Vec<String>,
+}
+
+/// CaptionCollector gathers word boundaries reported by *SpeechSynthesizer* so that
+/// captions are timed by the same run which produced the audio. The clone passed
+/// to the word boundary callback feeds the collector it was cloned from.
+#[derive(Debug, Clone, Default)]
+pub struct CaptionCollector {
+    boundaries: EventCollector<Vec<SynthesisWordBoundary>>,
+}
+
+impl CaptionCollector {
+    pub fn new() -> Self {
+        CaptionCollector::default()
+    }
+
+    /// Feeds word boundaries of *synthesizer* into this collector. Synthesizer keeps
+    /// single word boundary callback, so the one set before is replaced.
+    pub fn attach(&self, synthesizer: &mut SpeechSynthesizer) -> Result<()> {
+        let collector = self.clone();
+        synthesizer.set_synthesizer_word_boundary_cb(move |event| collector.push((&event).into()))
+    }
+
+    pub fn push(&self, boundary: SynthesisWordBoundary) {
+        self.boundaries.lock().push(boundary);
+    }
+
+    /// Removes boundaries collected so far, call it once the synthesis completes
+    /// so that captions of the next one do not include them.
+    pub fn take(&self) -> Vec<SynthesisWordBoundary> {
+        self.boundaries.take()
+    }
+
+    pub fn captions(&self, config: &CaptionConfig) -> Vec<Caption> {
+        build_captions(&self.boundaries.lock(), config)
+    }
+
+    pub fn to_srt(&self, config: &CaptionConfig) -> String {
+        to_srt(&self.captions(config))
+    }
+
+    pub fn to_webvtt(&self, config: &CaptionConfig) -> String {
+        to_webvtt(&self.captions(config))
+    }
+}
+
+/// Cue being built by *build_captions*.
+struct CueBuilder {
+    start: u64,
+    end: u64,
+    lines: Vec<String>,
+}
+
+impl CueBuilder {
+    fn chars(&self) -> usize {
+        self.lines.iter().map(|line| line.chars().count()).sum()
+    }
+}
+
+/// Creates captions from word boundaries (audio offsets in 100 ns ticks). Cues end at sentence
+/// boundaries, at phrase punctuation once cue is half full, or when the next word does not fit
+/// into *max_lines_per_cue* lines of *max_chars_per_line* characters.
+/// Opening punctuation (quotes, brackets, *¿*, *¡*) is attached to the following word,
+/// other punctuation to the preceding one.
+pub fn build_captions(
+    boundaries: &[SynthesisWordBoundary],
+    config: &CaptionConfig,
+) -> Vec<Caption> {
+    let max_chars = config.max_chars_per_line.max(1);
+    let max_lines = config.max_lines_per_cue.max(1);
+    let mut boundaries: Vec<&SynthesisWordBoundary> = boundaries.iter().collect();
+    boundaries.sort_by_key(|boundary| boundary.audio_offset);
+
+    let mut cues: Vec<CueBuilder> = vec![];
+    let mut current: Option<CueBuilder> = None;
+    let mut opening = String::new();
+    for boundary in boundaries {
+        let text = boundary.text.trim();
+        let end = boundary.audio_offset + boundary.duration;
+        match boundary.boundary_type {
+            SpeechSynthesisBoundaryType::SentenceBoundary => {
+                cues.extend(current.take());
+            }
+            SpeechSynthesisBoundaryType::PunctuationBoundary
+                if is_opening_punctuation(text, current.as_ref()) =>
+            {
+                opening.push_str(text);
+            }
+            SpeechSynthesisBoundaryType::PunctuationBoundary => {
+                if let Some(cue) = current.as_mut() {
+                    if let Some(line) = cue.lines.last_mut() {
+                        line.push_str(text);
+                    }
+                    cue.end = cue.end.max(end);
+                    let sentence_end = text.ends_with(['.', '!', '?', '…', '。', '！', '？']);
+                    let phrase_end = text.ends_with([',', ';', ':', '，', '；', '：', '、']);
+                    if sentence_end || (phrase_end && 2 * cue.chars() >= max_chars * max_lines) {
+                        cues.extend(current.take());
+                    }
+                }
+            }
+            SpeechSynthesisBoundaryType::WordBoundary if text.is_empty() => {}
+            SpeechSynthesisBoundaryType::WordBoundary => {
+                let word = std::mem::take(&mut opening) + text;
+                let text = word.as_str();
+                let cue = current.get_or_insert_with(|| CueBuilder {
+                    start: boundary.audio_offset,
+                    end,
+                    lines: vec![String::new()],
+                });
+                let line = cue.lines.last().expect("cue has a line");
+                let separator = if needs_space(line, text) { " " } else { "" };
+                if line.chars().count() + separator.len() + text.chars().count() <= max_chars
+                    || line.is_empty()
+                {
+                    let line = cue.lines.last_mut().expect("cue has a line");
+                    line.push_str(separator);
+                    line.push_str(text);
+                    cue.end = cue.end.max(end);
+                } else if cue.lines.len() < max_lines {
+                    cue.lines.push(text.to_owned());
+                    cue.end = cue.end.max(end);
+                } else {
+                    cues.extend(current.replace(CueBuilder {
+                        start: boundary.audio_offset,
+                        end,
+                        lines: vec![text.to_owned()],
+                    }));
+                }
+            }
+        }
+    }
+    cues.extend(current);
+
+    let min_duration = config.min_cue_duration.as_nanos() as u64 / 100;
+    let starts: Vec<u64> = cues.iter().skip(1).map(|cue| cue.start).collect();
+    cues.into_iter()
+        .enumerate()
+        .map(|(index, cue)| {
+            let mut end = cue.end.max(cue.start + min_duration);
+            if let Some(next_start) = starts.get(index) {
+                end = end.min(*next_start).max(cue.end);
+            }
+            Caption {
+                start: ticks_to_duration(cue.start),
+                end: ticks_to_duration(end),
+                lines: cue.lines,
+            }
+        })
+        .collect()
+}
+
+/// Straight quotes are opening unless the cue already contains unmatched quote of the same kind.
+fn is_opening_punctuation(text: &str, cue: Option<&CueBuilder>) -> bool {
+    match text.chars().next() {
+        Some(quote @ ('"' | '\'')) => cue.is_none_or(|cue| {
+            let count = cue
+                .lines
+                .iter()
+                .flat_map(|line| line.chars())
+                .filter(|c| *c == quote)
+                .count();
+            count % 2 == 0
+        }),
+        Some(c) => matches!(
+            c,
+            '“' | '‘'
+                | '„'
+                | '‚'
+                | '«'
+                | '‹'
+                | '('
+                | '['
+                | '{'
+                | '¿'
+                | '¡'
+                | '「'
+                | '『'
+                | '（'
+                | '【'
+                | '《'
+        ),
+        None => false,
+    }
+}
+
+fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::from_nanos(ticks * 100)
+}
+
+/// Words of languages written without spaces (Chinese, Japanese) are joined directly.
+fn needs_space(line: &str, word: &str) -> bool {
+    let is_cjk = |c: char| matches!(c, '\u{3000}'..='\u{30FF}' | '\u{3400}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' | '\u{FF00}'..='\u{FFEF}');
+    match (line.chars().last(), word.chars().next()) {
+        (Some(last), Some(first)) => !(is_cjk(last) || is_cjk(first)),
+        _ => false,
+    }
+}
+
+fn format_timestamp(time: Duration, fraction_separator: char) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        fraction_separator,
+        millis % 1000
+    )
+}
+
+/// Renders captions in SubRip (SRT) format.
+pub fn to_srt(captions: &[Caption]) -> String {
+    let mut srt = String::new();
+    for (index, caption) in captions.iter().enumerate() {
+        let _ = write!(
+            srt,
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(caption.start, ','),
+            format_timestamp(caption.end, ','),
+            caption.lines.join("\n")
+        );
+    }
+    srt
+}
+
+/// Renders captions in WebVTT format, *&*, *<* and *>* of the cue text are escaped.
+pub fn to_webvtt(captions: &[Caption]) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+    for caption in captions {
+        let text = caption
+            .lines
+            .join("\n")
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        let _ = write!(
+            vtt,
+            "{} --> {}\n{}\n\n",
+            format_timestamp(caption.start, '.'),
+            format_timestamp(caption.end, '.'),
+            text
+        );
+    }
+    vtt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boundary(ms: u64, text: &str) -> SynthesisWordBoundary {
+        let boundary_type = if text.chars().all(char::is_alphanumeric) {
+            SpeechSynthesisBoundaryType::WordBoundary
+        } else {
+            SpeechSynthesisBoundaryType::PunctuationBoundary
+        };
+        SynthesisWordBoundary {
+            audio_offset: ms * 10_000,
+            duration: 200 * 10_000,
+            text_offset: None,
+            word_length: text.len() as u32,
+            boundary_type,
+            text: text.to_owned(),
+        }
+    }
+
+    #[test]
+    fn builds_srt_and_webvtt() {
+        let words = [
+            "Hello", ".", "This", "caption", "is", "long", "enough", "to", "wrap", ",", "really",
+            ".",
+        ];
+        let boundaries: Vec<SynthesisWordBoundary> = words
+            .iter()
+            .enumerate()
+            .map(|(index, word)| boundary(index as u64 * 300, word))
+            .collect();
+        let config = CaptionConfig {
+            max_chars_per_line: 12,
+            max_lines_per_cue: 2,
+            min_cue_duration: Duration::from_millis(800),
+        };
+        let captions = build_captions(&boundaries, &config);
+        assert_eq!(
+            to_srt(&captions),
+            "1\n00:00:00,000 --> 00:00:00,600\nHello.\n\n\
+             2\n00:00:00,600 --> 00:00:01,700\nThis caption\nis long\n\n\
+             3\n00:00:01,800 --> 00:00:02,900\nenough to\nwrap,\n\n\
+             4\n00:00:03,000 --> 00:00:03,800\nreally.\n\n"
+        );
+        assert!(to_webvtt(&captions).starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:00.600\nHello."));
+    }
+
+    #[test]
+    fn attaches_opening_punctuation_and_escapes_webvtt() {
+        let words = [
+            "He", "said", "\"", "hi", "\"", "(", "A", "B", ")", "¿", "Qué", "?",
+        ];
+        let boundaries: Vec<SynthesisWordBoundary> = words
+            .iter()
+            .enumerate()
+            .map(|(index, word)| boundary(index as u64 * 300, word))
+            .collect();
+        let config = CaptionConfig {
+            max_chars_per_line: 80,
+            ..CaptionConfig::default()
+        };
+        let captions = build_captions(&boundaries, &config);
+        assert_eq!(
+            captions[0].lines,
+            vec!["He said \"hi\" (A B) ¿Qué?".to_owned()]
+        );
+
+        let captions = [Caption {
+            start: Duration::from_secs(1),
+            end: Duration::from_secs(2),
+            lines: vec!["<b>Q&A</b>".to_owned()],
+        }];
+        assert!(to_srt(&captions).contains("\n<b>Q&A</b>\n"));
+        assert!(to_webvtt(&captions).contains("\n&lt;b&gt;Q&amp;A&lt;/b&gt;\n"));
+    }
+}
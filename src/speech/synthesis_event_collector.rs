@@ -0,0 +1,33 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// State filled by synthesizer callbacks and read by the owner of the synthesizer,
+/// both sides hold a clone referring to the same state.
+#[derive(Debug, Default)]
+pub(crate) struct EventCollector<T> {
+    state: Arc<Mutex<T>>,
+}
+
+impl<T> Clone for EventCollector<T> {
+    fn clone(&self) -> Self {
+        EventCollector {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T> EventCollector<T> {
+    pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+        // collected events stay usable even if a callback panicked while holding the lock
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns collected state and resets it for the next synthesis.
+    pub(crate) fn take(&self) -> T
+    where
+        T: Default,
+    {
+        std::mem::take(&mut *self.lock())
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::Result;
+use log::*;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Items shared by workers of *run_workers*, each item is taken by exactly one worker.
+pub(crate) struct WorkQueue<T, O> {
+    items: Vec<T>,
+    next: AtomicUsize,
+    outputs: Mutex<Vec<Option<O>>>,
+    runtime: tokio::runtime::Handle,
+}
+
+impl<T, O> WorkQueue<T, O> {
+    /// Returns the next item with its index, *None* once all items are taken.
+    pub(crate) fn take(&self) -> Option<(usize, &T)> {
+        let index = self.next.fetch_add(1, Ordering::SeqCst);
+        self.items.get(index).map(|item| (index, item))
+    }
+
+    pub(crate) fn complete(&self, index: usize, output: O) {
+        self.outputs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())[index] = Some(output);
+    }
+
+    /// Waits for the future on the runtime which started the workers.
+    pub(crate) fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Makes remaining items unavailable to all workers.
+    fn stop(&self) {
+        self.next.store(self.items.len(), Ordering::SeqCst);
+    }
+}
+
+/// Processes *items* by *concurrency* blocking tasks (synthesis calls block until done),
+/// each running *worker* which takes items from the queue until it is empty.
+/// When a worker returns error the remaining items are dropped and the first error
+/// is returned. Outputs are returned in the order of items, items not completed because
+/// their worker panicked are *Err* with the panic message, other workers carry on.
+pub(crate) async fn run_workers<T, O, W>(
+    items: Vec<T>,
+    concurrency: usize,
+    worker: W,
+) -> Result<Vec<std::result::Result<O, String>>>
+where
+    T: Send + Sync + 'static,
+    O: Send + 'static,
+    W: Fn(&WorkQueue<T, O>) -> Result<()> + Send + Sync + 'static,
+{
+    let len = items.len();
+    let queue = Arc::new(WorkQueue {
+        items,
+        next: AtomicUsize::new(0),
+        outputs: Mutex::new((0..len).map(|_| None).collect()),
+        runtime: tokio::runtime::Handle::current(),
+    });
+    let worker = Arc::new(worker);
+    let mut workers = vec![];
+    for _ in 0..concurrency.min(len).max(1) {
+        let queue = queue.clone();
+        let worker = worker.clone();
+        workers.push(tokio::task::spawn_blocking(move || {
+            let result = worker(&queue);
+            if result.is_err() {
+                queue.stop();
+            }
+            result
+        }));
+    }
+
+    let mut first_error = None;
+    let mut panic_message = String::from("worker did not run");
+    for worker in workers {
+        match worker.await {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                first_error.get_or_insert(err);
+            }
+            Err(err) => {
+                error!("run_workers worker failed: {}", err);
+                panic_message = format!("worker failed: {}", err);
+            }
+        }
+    }
+    if let Some(err) = first_error {
+        return Err(err);
+    }
+    let outputs = std::mem::take(
+        &mut *queue
+            .outputs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+    );
+    Ok(outputs
+        .into_iter()
+        .map(|output| output.ok_or_else(|| panic_message.clone()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn keeps_outputs_of_other_workers_when_one_panics() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let outputs = runtime
+            .block_on(run_workers((0..20).collect(), 3, |queue| {
+                while let Some((index, item)) = queue.take() {
+                    assert_ne!(*item, 7, "item 7");
+                    queue.complete(index, item * 2);
+                }
+                Ok(())
+            }))
+            .unwrap();
+        assert_eq!(outputs.len(), 20);
+        for (index, output) in outputs.iter().enumerate() {
+            match output {
+                Ok(output) => assert_eq!(*output, index * 2),
+                Err(message) => {
+                    assert_eq!(index, 7);
+                    assert!(message.contains("item 7"), "{}", message);
+                }
+            }
+        }
+
+        let result = runtime.block_on(run_workers(vec![1, 2, 3], 2, |queue| {
+            while let Some((index, item)) = queue.take() {
+                if *item == 2 {
+                    return Err(io::Error::other("failed").into());
+                }
+                queue.complete(index, *item);
+            }
+            Ok(())
+        }));
+        assert!(result.is_err());
+    }
+}
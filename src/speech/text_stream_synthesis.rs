@@ -0,0 +1,236 @@
+use crate::common::StreamStatus;
+use crate::error::{Error, ErrorRootCause, Result};
+use crate::speech::{AudioDataStream, SpeechSynthesisRequest};
+use futures_core::Stream;
+use log::*;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+/// Size of audio chunks read from *AudioDataStream*.
+const AUDIO_CHUNK_SIZE: usize = 4800;
+
+/// SentenceChunker accumulates incoming text pieces (e.g. LLM tokens) and releases them
+/// at sentence boundaries, so that synthesis gets enough context for natural prosody
+/// while keeping latency low. Sentences longer than *max_chars* are split at phrase
+/// boundaries (commas, semicolons...) or whitespace.
+#[derive(Debug, Clone)]
+pub struct SentenceChunker {
+    buffer: String,
+    min_chars: usize,
+    max_chars: usize,
+}
+
+impl Default for SentenceChunker {
+    fn default() -> Self {
+        SentenceChunker::new(10, 200)
+    }
+}
+
+impl SentenceChunker {
+    /// *min_chars* is minimal length of released chunk (shorter sentences are merged
+    /// with the following one), *max_chars* is length after which text is released
+    /// even when no sentence boundary was found.
+    pub fn new(min_chars: usize, max_chars: usize) -> Self {
+        SentenceChunker {
+            buffer: String::new(),
+            min_chars,
+            max_chars: max_chars.max(1),
+        }
+    }
+
+    /// Adds text piece, returning chunks ready to be synthesized.
+    pub fn push(&mut self, piece: &str) -> Vec<String> {
+        self.buffer.push_str(piece);
+        let mut chunks = vec![];
+        while let Some(chunk) = self.next_chunk() {
+            chunks.push(chunk);
+        }
+        chunks
+    }
+
+    /// Returns remaining buffered text once the input has ended.
+    pub fn finish(&mut self) -> Option<String> {
+        let rest = std::mem::take(&mut self.buffer);
+        if rest.trim().is_empty() {
+            None
+        } else {
+            Some(rest)
+        }
+    }
+
+    fn next_chunk(&mut self) -> Option<String> {
+        let mut end = None;
+        let mut chars = self.buffer.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            let is_boundary = match c {
+                '\n' | '。' | '！' | '？' => true,
+                // terminator must be followed by whitespace, e.g. not decimal point
+                '.' | '!' | '?' | '…' | ';' => {
+                    matches!(chars.peek(), Some((_, next)) if next.is_whitespace())
+                }
+                _ => false,
+            };
+            let boundary = index + c.len_utf8();
+            if is_boundary && self.buffer[..boundary].trim().chars().count() >= self.min_chars {
+                end = Some(boundary);
+                break;
+            }
+        }
+
+        if end.is_none() && self.buffer.chars().count() > self.max_chars {
+            let limit = self
+                .buffer
+                .char_indices()
+                .nth(self.max_chars)
+                .map(|(index, _)| index)
+                .unwrap_or(self.buffer.len());
+            let head = &self.buffer[..limit];
+            end = head
+                .char_indices()
+                .rev()
+                .find(|(_, c)| matches!(c, ',' | ':' | '，' | '、' | '；' | '：'))
+                .map(|(index, c)| index + c.len_utf8())
+                .or_else(|| head.rfind(char::is_whitespace).filter(|index| *index > 0))
+                .or(Some(limit));
+        }
+
+        let end = end?;
+        Some(self.buffer.drain(..end).collect())
+    }
+}
+
+/// Asynchronous stream of synthesized audio chunks created by
+/// *SpeechSynthesizer::speak_text_stream*. Stream ends once the
+/// synthesis is completed and yields error when it is canceled.
+#[derive(Debug)]
+pub struct SynthesisAudioStream {
+    rx: mpsc::Receiver<Result<Vec<u8>>>,
+}
+
+impl SynthesisAudioStream {
+    /// Starts feeding text pieces into the request and reading synthesized audio.
+    pub(crate) fn spawn<S>(
+        request: SpeechSynthesisRequest,
+        audio: AudioDataStream,
+        mut text: S,
+        mut chunker: SentenceChunker,
+    ) -> Self
+    where
+        S: Stream<Item = String> + Send + Unpin + 'static,
+    {
+        let (tx, rx) = mpsc::channel(16);
+
+        let text_tx = tx.clone();
+        tokio::spawn(async move {
+            let send = |chunk: String| -> Result<()> {
+                trace!("SynthesisAudioStream sending text piece {:?}", chunk);
+                request.send_text_piece(&chunk)
+            };
+            let mut sent = Ok(());
+            while sent.is_ok() {
+                let Some(piece) = poll_fn(|cx| Pin::new(&mut text).poll_next(cx)).await else {
+                    sent = chunker.finish().map_or(Ok(()), send);
+                    break;
+                };
+                for chunk in chunker.push(&piece) {
+                    sent = sent.and_then(|_| send(chunk));
+                }
+            }
+            if let Err(err) = sent {
+                // channel is shared with audio chunks, wait for free slot so that the error is not lost
+                if text_tx.send(Err(err)).await.is_err() {
+                    trace!("SynthesisAudioStream receiver dropped");
+                }
+            }
+            if let Err(err) = request.finish_input() {
+                error!("SynthesisAudioStream finish_input error: {}", err);
+            }
+        });
+
+        // AudioDataStream::read blocks until data are available, use dedicated thread
+        std::thread::spawn(move || {
+            let mut buf = vec![0u8; AUDIO_CHUNK_SIZE];
+            loop {
+                match audio.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        if tx.blocking_send(Ok(buf[..n as usize].to_vec())).is_err() {
+                            trace!("SynthesisAudioStream receiver dropped");
+                            return;
+                        }
+                    }
+                    Err(err) => {
+                        let _ = tx.blocking_send(Err(err));
+                        return;
+                    }
+                }
+            }
+            if let Ok(StreamStatus::StreamStatusCanceled) = audio.get_status() {
+                let _ = tx.blocking_send(Err(canceled_error(&audio)));
+            }
+        });
+
+        SynthesisAudioStream { rx }
+    }
+
+    /// Receives next chunk, *None* signals end of the audio.
+    pub async fn recv(&mut self) -> Option<Result<Vec<u8>>> {
+        self.rx.recv().await
+    }
+}
+
+impl Stream for SynthesisAudioStream {
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+fn canceled_error(audio: &AudioDataStream) -> Error {
+    let rootc = ErrorRootCause::ApiError(0x032);
+    let message = format!(
+        "{}: synthesis canceled, reason {:?}, error code {:?}: {}",
+        Error::api_error_desc(&rootc).unwrap_or_default(),
+        audio.get_reason_canceled().ok(),
+        audio.get_canceled_error_code().ok(),
+        audio.get_canceled_error_details().unwrap_or_default()
+    );
+    Error::new(message, rootc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_at_sentence_boundaries() {
+        let mut chunker = SentenceChunker::new(10, 40);
+        let mut chunks = vec![];
+        for token in [
+            "Hi. ",
+            "It costs 3",
+            ".14 dollars",
+            ". Next",
+            " sentence is ",
+            "rather long, ",
+            "so it gets split somewhere in the middle",
+            " of it",
+            "",
+        ] {
+            chunks.extend(chunker.push(token));
+        }
+        chunks.extend(chunker.finish());
+        assert_eq!(
+            chunks,
+            vec![
+                "Hi. It costs 3.14 dollars.",
+                " Next sentence is rather long,",
+                " so it gets split somewhere in the",
+                " middle of it",
+            ]
+        );
+    }
+}
@@ -0,0 +1,411 @@
+use crate::error::Result;
+use crate::speech::synthesis_event_collector::EventCollector;
+use crate::speech::{SpeechSynthesisVisemeEvent, SpeechSynthesizer};
+use std::fmt::Write;
+use std::io;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Number of blend shapes in each frame of facial expression animation.
+pub const BLEND_SHAPE_COUNT: usize = 55;
+
+/// Facial expression animation is reported at 60 frames per second.
+pub const VISEME_FRAMES_PER_SECOND: u64 = 60;
+
+/// Names of blend shapes in the order of *VisemeFrame::blend_shapes*.
+pub const BLEND_SHAPE_NAMES: [&str; BLEND_SHAPE_COUNT] = [
+    "eyeBlinkLeft",
+    "eyeLookDownLeft",
+    "eyeLookInLeft",
+    "eyeLookOutLeft",
+    "eyeLookUpLeft",
+    "eyeSquintLeft",
+    "eyeWideLeft",
+    "eyeBlinkRight",
+    "eyeLookDownRight",
+    "eyeLookInRight",
+    "eyeLookOutRight",
+    "eyeLookUpRight",
+    "eyeSquintRight",
+    "eyeWideRight",
+    "jawForward",
+    "jawLeft",
+    "jawRight",
+    "jawOpen",
+    "mouthClose",
+    "mouthFunnel",
+    "mouthPucker",
+    "mouthLeft",
+    "mouthRight",
+    "mouthSmileLeft",
+    "mouthSmileRight",
+    "mouthFrownLeft",
+    "mouthFrownRight",
+    "mouthDimpleLeft",
+    "mouthDimpleRight",
+    "mouthStretchLeft",
+    "mouthStretchRight",
+    "mouthRollLower",
+    "mouthRollUpper",
+    "mouthShrugLower",
+    "mouthShrugUpper",
+    "mouthPressLeft",
+    "mouthPressRight",
+    "mouthLowerDownLeft",
+    "mouthLowerDownRight",
+    "mouthUpperUpLeft",
+    "mouthUpperUpRight",
+    "browDownLeft",
+    "browDownRight",
+    "browInnerUp",
+    "browOuterUpLeft",
+    "browOuterUpRight",
+    "cheekPuff",
+    "cheekSquintLeft",
+    "cheekSquintRight",
+    "noseSneerLeft",
+    "noseSneerRight",
+    "tongueOut",
+    "headRoll",
+    "leftEyeRoll",
+    "rightEyeRoll",
+];
+
+/// Single frame of facial expression animation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VisemeFrame {
+    /// Index of the frame counted from the start of the synthesis.
+    pub index: u64,
+    /// Audio offset of the frame in ticks (100 nanoseconds).
+    pub audio_offset: u64,
+    /// *BLEND_SHAPE_COUNT* weights in the order of *BLEND_SHAPE_NAMES*.
+    pub blend_shapes: Vec<f32>,
+}
+
+impl VisemeFrame {
+    fn new(index: u64, blend_shapes: Vec<f32>) -> Self {
+        VisemeFrame {
+            index,
+            audio_offset: index * 10_000_000 / VISEME_FRAMES_PER_SECOND,
+            blend_shapes,
+        }
+    }
+
+    pub fn audio_time(&self) -> Duration {
+        Duration::from_nanos(self.audio_offset * 100)
+    }
+
+    /// Returns weight of blend shape by its name, e.g. *jawOpen*.
+    pub fn blend_shape(&self, name: &str) -> Option<f32> {
+        let position = BLEND_SHAPE_NAMES.iter().position(|n| *n == name)?;
+        self.blend_shapes.get(position).copied()
+    }
+}
+
+/// Animation chunk carried by *SpeechSynthesisVisemeEvent::animation* when SSML
+/// requests *mstts:viseme type="FacialExpression"*.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VisemeAnimation {
+    /// Index of the first frame of the chunk.
+    pub frame_index: u64,
+    pub frames: Vec<VisemeFrame>,
+}
+
+impl VisemeAnimation {
+    /// Parses animation JSON of form {"FrameIndex":0,"BlendShapes":[[..55 weights..],..]}.
+    pub fn parse(animation: &str) -> Result<Self> {
+        let frame_index = JsonScanner::after_key(animation, "FrameIndex")?.number()? as u64;
+        let mut scanner = JsonScanner::after_key(animation, "BlendShapes")?;
+        let mut frames = vec![];
+        scanner.expect('[')?;
+        if !scanner.consume(']') {
+            loop {
+                scanner.expect('[')?;
+                let mut blend_shapes = Vec::with_capacity(BLEND_SHAPE_COUNT);
+                if !scanner.consume(']') {
+                    loop {
+                        blend_shapes.push(scanner.number()? as f32);
+                        if scanner.consume(']') {
+                            break;
+                        }
+                        scanner.expect(',')?;
+                    }
+                }
+                if blend_shapes.len() != BLEND_SHAPE_COUNT {
+                    return Err(invalid_animation(&format!(
+                        "frame has {} blend shapes, expected {}",
+                        blend_shapes.len(),
+                        BLEND_SHAPE_COUNT
+                    )));
+                }
+                frames.push(VisemeFrame::new(
+                    frame_index + frames.len() as u64,
+                    blend_shapes,
+                ));
+                if scanner.consume(']') {
+                    break;
+                }
+                scanner.expect(',')?;
+            }
+        }
+        Ok(VisemeAnimation {
+            frame_index,
+            frames,
+        })
+    }
+}
+
+impl SpeechSynthesisVisemeEvent {
+    /// Parses *animation* of the event, returns *None* when the event carries no animation.
+    pub fn parse_animation(&self) -> Result<Option<VisemeAnimation>> {
+        if self.animation.trim().is_empty() {
+            return Ok(None);
+        }
+        VisemeAnimation::parse(&self.animation).map(Some)
+    }
+}
+
+fn invalid_animation(msg: &str) -> crate::error::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid viseme animation: {}", msg),
+    )
+    .into()
+}
+
+/// Minimal scanner of the fixed shape animation JSON, so that parsing does not
+/// depend on *serde* feature.
+struct JsonScanner<'a> {
+    rest: &'a str,
+}
+
+impl<'a> JsonScanner<'a> {
+    fn after_key(json: &'a str, key: &str) -> Result<Self> {
+        let quoted = format!("\"{}\"", key);
+        let start = json
+            .find(&quoted)
+            .ok_or_else(|| invalid_animation(&format!("missing {}", key)))?;
+        let mut scanner = JsonScanner {
+            rest: &json[start + quoted.len()..],
+        };
+        scanner.expect(':')?;
+        Ok(scanner)
+    }
+
+    fn consume(&mut self, c: char) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.consume(c) {
+            Ok(())
+        } else {
+            Err(invalid_animation(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        self.rest = self.rest.trim_start();
+        let end = self
+            .rest
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+            .unwrap_or(self.rest.len());
+        let number = self.rest[..end]
+            .parse()
+            .map_err(|_| invalid_animation(&format!("invalid number {:?}", &self.rest[..end])))?;
+        self.rest = &self.rest[end..];
+        Ok(number)
+    }
+}
+
+/// Viseme id reported at the audio offset (in ticks).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Viseme {
+    pub audio_offset: u64,
+    pub viseme_id: u32,
+}
+
+/// VisemeTimeline joins viseme events of one synthesis into continuous timeline
+/// of viseme ids and animation frames ordered by audio time.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VisemeTimeline {
+    pub visemes: Vec<Viseme>,
+    pub frames: Vec<VisemeFrame>,
+}
+
+impl VisemeTimeline {
+    pub fn new() -> Self {
+        VisemeTimeline::default()
+    }
+
+    pub fn push_event(&mut self, event: &SpeechSynthesisVisemeEvent) -> Result<()> {
+        let viseme = Viseme {
+            audio_offset: event.audio_offset,
+            viseme_id: event.viseme_id,
+        };
+        let position = self
+            .visemes
+            .partition_point(|v| v.audio_offset <= viseme.audio_offset);
+        self.visemes.insert(position, viseme);
+        if let Some(animation) = event.parse_animation()? {
+            self.push_animation(animation);
+        }
+        Ok(())
+    }
+
+    /// Adds frames of the animation, frames with already present index are replaced.
+    pub fn push_animation(&mut self, animation: VisemeAnimation) {
+        for frame in animation.frames {
+            match self.frames.binary_search_by_key(&frame.index, |f| f.index) {
+                Ok(position) => self.frames[position] = frame,
+                Err(position) => self.frames.insert(position, frame),
+            }
+        }
+    }
+
+    /// Returns the last frame at or before the audio time.
+    pub fn frame_at(&self, time: Duration) -> Option<&VisemeFrame> {
+        let offset = time.as_nanos() as u64 / 100;
+        let position = self.frames.partition_point(|f| f.audio_offset <= offset);
+        position
+            .checked_sub(1)
+            .map(|position| &self.frames[position])
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.frames
+            .last()
+            .map(|frame| frame.audio_time())
+            .unwrap_or_default()
+    }
+
+    /// Exports frames as CSV with header *time_ms,frame_index* followed by blend shape names.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time_ms,frame_index");
+        for name in BLEND_SHAPE_NAMES.iter() {
+            csv.push(',');
+            csv.push_str(name);
+        }
+        csv.push('\n');
+        for frame in &self.frames {
+            let _ = write!(
+                csv,
+                "{:.3},{}",
+                frame.audio_offset as f64 / 10_000.0,
+                frame.index
+            );
+            for weight in &frame.blend_shapes {
+                let _ = write!(csv, ",{}", weight);
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// VisemeCollector builds *VisemeTimeline* of one synthesis from viseme events reported
+/// by *SpeechSynthesizer*, e.g. to drive avatar animation after the audio is received.
+/// Viseme callback holds a clone of the collector and adds events to its timeline.
+#[derive(Debug, Clone, Default)]
+pub struct VisemeCollector {
+    state: EventCollector<CollectedVisemes>,
+}
+
+#[derive(Debug, Default)]
+struct CollectedVisemes {
+    timeline: VisemeTimeline,
+    /// First animation parsing error, reported by *VisemeCollector::timeline*.
+    error: Option<String>,
+}
+
+impl VisemeCollector {
+    pub fn new() -> Self {
+        VisemeCollector::default()
+    }
+
+    /// Takes over viseme callback of *synthesizer* to build the timeline.
+    pub fn attach(&self, synthesizer: &mut SpeechSynthesizer) -> Result<()> {
+        let collector = self.clone();
+        synthesizer.set_synthesizer_viseme_cb(move |event| collector.push_event(&event))
+    }
+
+    /// Adds the event, first animation parsing error is kept and returned by *timeline*.
+    pub fn push_event(&self, event: &SpeechSynthesisVisemeEvent) {
+        let mut state = self.state.lock();
+        if let Err(err) = state.timeline.push_event(event) {
+            state.error.get_or_insert_with(|| err.to_string());
+        }
+    }
+
+    /// Returns timeline built from events since the previous call, the next
+    /// synthesis starts a new one. Fails when animation of any event was invalid.
+    pub fn timeline(&self) -> Result<VisemeTimeline> {
+        let collected = self.state.take();
+        match collected.error {
+            Some(error) => Err(invalid_animation(&error)),
+            None => Ok(collected.timeline),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animation_json(frame_index: u64, frames: usize) -> String {
+        let frame = format!("[{}0.5]", "0,".repeat(BLEND_SHAPE_COUNT - 1));
+        format!(
+            "{{\"FrameIndex\":{},\"BlendShapes\":[{}]}}",
+            frame_index,
+            vec![frame; frames].join(", ")
+        )
+    }
+
+    #[test]
+    fn parses_animation_into_timeline() {
+        let first = VisemeAnimation::parse(&animation_json(0, 2)).unwrap();
+        assert_eq!(first.frames.len(), 2);
+        assert_eq!(first.frames[1].blend_shape("rightEyeRoll"), Some(0.5));
+        assert_eq!(first.frames[1].blend_shape("jawOpen"), Some(0.0));
+
+        let mut timeline = VisemeTimeline::new();
+        timeline.push_animation(VisemeAnimation::parse(&animation_json(60, 1)).unwrap());
+        timeline.push_animation(first);
+        assert_eq!(
+            timeline.frames.iter().map(|f| f.index).collect::<Vec<_>>(),
+            vec![0, 1, 60]
+        );
+        assert_eq!(timeline.duration(), Duration::from_secs(1));
+        assert_eq!(
+            timeline.frame_at(Duration::from_millis(500)).unwrap().index,
+            1
+        );
+
+        let csv = timeline.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].starts_with("16.667,1,0,"));
+        assert!(lines[3].starts_with("1000.000,60,"));
+
+        assert!(VisemeAnimation::parse("{\"FrameIndex\":0,\"BlendShapes\":[[0.1,0.2]]}").is_err());
+        assert!(VisemeAnimation::parse("{\"FrameIndex\":0}").is_err());
+    }
+}
@@ -0,0 +1,405 @@
+use crate::common::{ResultReason, SynthesisVoiceType};
+use crate::error::{Error, ErrorRootCause, Result};
+use crate::speech::{SpeechSynthesizer, SsmlVoiceChecker, SynthesisVoicesResult, VoiceInfo};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+/// Names of voice properties holding metadata not exposed by *VoiceInfo* fields.
+const PROP_GENDER: &str = "Gender";
+const PROP_SAMPLE_RATE_HERTZ: &str = "SampleRateHertz";
+const PROP_WORDS_PER_MINUTE: &str = "WordsPerMinute";
+const PROP_ROLE_PLAY_LIST: &str = "RolePlayList";
+const PROP_SECONDARY_LOCALE_LIST: &str = "SecondaryLocaleList";
+
+/// Gender of synthesis voice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Gender {
+    Female,
+    Male,
+    Neutral,
+    Unknown,
+}
+
+impl Gender {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "female" => Gender::Female,
+            "male" => Gender::Male,
+            "neutral" => Gender::Neutral,
+            _ => Gender::Unknown,
+        }
+    }
+}
+
+/// Voice of *VoiceCatalog*, plain data copy of *VoiceInfo* including metadata
+/// parsed from its properties, so that it can be cached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CatalogVoice {
+    pub name: String,
+    pub short_name: String,
+    pub local_name: String,
+    pub locale: String,
+    pub gender: Gender,
+    pub voice_type: SynthesisVoiceType,
+    pub styles: Vec<String>,
+    /// *None* when the service does not report roles of the voice.
+    pub roles: Option<Vec<String>>,
+    pub secondary_locales: Vec<String>,
+    pub sample_rate_hertz: Option<u32>,
+    pub words_per_minute: Option<u32>,
+    pub voice_path: String,
+}
+
+impl CatalogVoice {
+    pub fn is_neural(&self) -> bool {
+        matches!(
+            self.voice_type,
+            SynthesisVoiceType::OnlineNeural | SynthesisVoiceType::OfflineNeural
+        )
+    }
+
+    pub fn is_multilingual(&self) -> bool {
+        self.short_name.contains("Multilingual") || !self.secondary_locales.is_empty()
+    }
+
+    pub fn supports_style(&self, style: &str) -> bool {
+        self.styles.iter().any(|s| s.eq_ignore_ascii_case(style))
+    }
+}
+
+impl From<&VoiceInfo> for CatalogVoice {
+    fn from(voice: &VoiceInfo) -> Self {
+        let property = |name: &str| -> String {
+            voice
+                .properties
+                .get_property_by_string(name, "")
+                .unwrap_or_default()
+        };
+        let list = |name: &str| -> Vec<String> {
+            property(name)
+                .split(['|', ','])
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_owned)
+                .collect()
+        };
+        let roles = list(PROP_ROLE_PLAY_LIST);
+        CatalogVoice {
+            name: voice.name.clone(),
+            short_name: voice.short_name.clone(),
+            local_name: voice.local_name.clone(),
+            locale: voice.locale.clone(),
+            gender: Gender::from_name(&property(PROP_GENDER)),
+            voice_type: voice.voice_type,
+            styles: voice
+                .style_list
+                .iter()
+                .filter(|style| !style.is_empty())
+                .cloned()
+                .collect(),
+            roles: if roles.is_empty() { None } else { Some(roles) },
+            secondary_locales: list(PROP_SECONDARY_LOCALE_LIST),
+            sample_rate_hertz: property(PROP_SAMPLE_RATE_HERTZ).parse().ok(),
+            words_per_minute: property(PROP_WORDS_PER_MINUTE).parse().ok(),
+            voice_path: voice.voice_path.clone(),
+        }
+    }
+}
+
+/// Filter of *VoiceCatalog::query*, unset criteria match any voice.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VoiceQuery {
+    locale: Option<String>,
+    gender: Option<Gender>,
+    voice_type: Option<SynthesisVoiceType>,
+    style: Option<String>,
+    neural: Option<bool>,
+    multilingual: Option<bool>,
+}
+
+impl VoiceQuery {
+    pub fn new() -> Self {
+        VoiceQuery::default()
+    }
+
+    /// Matches full locale (e.g. *en-US*) or language (e.g. *en*), case insensitive.
+    /// Multilingual voices match also their secondary locales.
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    pub fn gender(mut self, gender: Gender) -> Self {
+        self.gender = Some(gender);
+        self
+    }
+
+    pub fn voice_type(mut self, voice_type: SynthesisVoiceType) -> Self {
+        self.voice_type = Some(voice_type);
+        self
+    }
+
+    /// Matches voices supporting *mstts:express-as* style.
+    pub fn style(mut self, style: impl Into<String>) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+
+    pub fn neural(mut self, neural: bool) -> Self {
+        self.neural = Some(neural);
+        self
+    }
+
+    pub fn multilingual(mut self, multilingual: bool) -> Self {
+        self.multilingual = Some(multilingual);
+        self
+    }
+
+    pub fn matches(&self, voice: &CatalogVoice) -> bool {
+        self.locale.as_ref().is_none_or(|locale| {
+            std::iter::once(&voice.locale)
+                .chain(&voice.secondary_locales)
+                .any(|voice_locale| locale_matches(voice_locale, locale))
+        }) && self.gender.is_none_or(|gender| voice.gender == gender)
+            && self
+                .voice_type
+                .is_none_or(|voice_type| voice.voice_type == voice_type)
+            && self
+                .style
+                .as_ref()
+                .is_none_or(|style| voice.supports_style(style))
+            && self.neural.is_none_or(|neural| voice.is_neural() == neural)
+            && self
+                .multilingual
+                .is_none_or(|multilingual| voice.is_multilingual() == multilingual)
+    }
+}
+
+fn locale_matches(voice_locale: &str, locale: &str) -> bool {
+    voice_locale.eq_ignore_ascii_case(locale)
+        || (!locale.contains('-')
+            && voice_locale
+                .split('-')
+                .next()
+                .is_some_and(|language| language.eq_ignore_ascii_case(locale)))
+}
+
+/// VoiceCatalog holds voices returned by *SpeechSynthesizer::get_voices_async* and allows
+/// querying them. With *serde* feature it can be cached on disk, see *load_or_fetch*.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VoiceCatalog {
+    /// Time when the voices were retrieved from the service, in seconds since UNIX epoch.
+    pub fetched_at: u64,
+    pub voices: Vec<CatalogVoice>,
+}
+
+impl VoiceCatalog {
+    pub fn new(voices: Vec<CatalogVoice>) -> Self {
+        VoiceCatalog {
+            fetched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            voices,
+        }
+    }
+
+    /// Creates catalog from voices result, fails if retrieval of the voices was canceled.
+    pub fn from_voices_result(result: &SynthesisVoicesResult) -> Result<Self> {
+        if let ResultReason::Canceled = result.reason {
+            return Err(Error::new(
+                format!("voices list retrieval canceled: {}", result.error_details),
+                ErrorRootCause::ApiError(0x032),
+            ));
+        }
+        Ok(VoiceCatalog::new(
+            result.voices.iter().map(CatalogVoice::from).collect(),
+        ))
+    }
+
+    /// Retrieves voices of *locale* (empty string for all locales) from the service.
+    pub async fn fetch(synthesizer: &SpeechSynthesizer, locale: &str) -> Result<Self> {
+        let result = synthesizer.get_voices_async(locale).await?;
+        VoiceCatalog::from_voices_result(&result)
+    }
+
+    pub fn query(&self, query: &VoiceQuery) -> Vec<&CatalogVoice> {
+        self.voices
+            .iter()
+            .filter(|voice| query.matches(voice))
+            .collect()
+    }
+
+    /// Finds voice by short name (e.g. *en-US-AvaNeural*) or full name.
+    pub fn find(&self, name: &str) -> Option<&CatalogVoice> {
+        self.voices.iter().find(|voice| {
+            voice.short_name.eq_ignore_ascii_case(name) || voice.name.eq_ignore_ascii_case(name)
+        })
+    }
+
+    /// Returns sorted primary locales of all voices.
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = self.voices.iter().map(|v| v.locale.as_str()).collect();
+        locales.sort_unstable();
+        locales.dedup();
+        locales
+    }
+
+    pub fn age(&self) -> Duration {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Duration::from_secs(now.saturating_sub(self.fetched_at))
+    }
+
+    pub fn is_expired(&self, ttl: Duration) -> bool {
+        self.age() > ttl
+    }
+
+    /// Creates *SsmlVoiceChecker* validating SSML documents against this catalog.
+    pub fn ssml_checker(&self) -> SsmlVoiceChecker {
+        SsmlVoiceChecker::new(self.voices.iter().map(Into::into).collect())
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Loads cached catalog, returns *None* when cache does not exist or is older than *ttl*.
+    #[cfg(feature = "serde")]
+    pub fn load(path: impl AsRef<Path>, ttl: Duration) -> Result<Option<Self>> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let catalog = VoiceCatalog::from_json(&json)?;
+        Ok(if catalog.is_expired(ttl) {
+            None
+        } else {
+            Some(catalog)
+        })
+    }
+
+    /// Returns catalog cached in *path* unless expired, otherwise fetches
+    /// voices of *locale* from the service and refreshes the cache.
+    #[cfg(feature = "serde")]
+    pub async fn load_or_fetch(
+        path: impl AsRef<Path>,
+        ttl: Duration,
+        synthesizer: &SpeechSynthesizer,
+        locale: &str,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        match VoiceCatalog::load(path, ttl) {
+            Ok(Some(catalog)) => return Ok(catalog),
+            Ok(None) => {}
+            // corrupted cache is simply refreshed
+            Err(err) => log::warn!("VoiceCatalog cache {} error: {}", path.display(), err),
+        }
+        let catalog = VoiceCatalog::fetch(synthesizer, locale).await?;
+        catalog.save(path)?;
+        Ok(catalog)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voice(short_name: &str, gender: Gender, styles: &[&str]) -> CatalogVoice {
+        CatalogVoice {
+            name: format!(
+                "Microsoft Server Speech Text to Speech Voice ({})",
+                short_name
+            ),
+            short_name: short_name.to_owned(),
+            local_name: short_name.to_owned(),
+            locale: short_name[..5].to_owned(),
+            gender,
+            voice_type: SynthesisVoiceType::OnlineNeural,
+            styles: styles.iter().map(|s| s.to_string()).collect(),
+            roles: None,
+            secondary_locales: vec![],
+            sample_rate_hertz: Some(24000),
+            words_per_minute: None,
+            voice_path: String::new(),
+        }
+    }
+
+    #[test]
+    fn queries_voices() {
+        let mut catalog = VoiceCatalog::new(vec![
+            voice("en-US-JennyNeural", Gender::Female, &["cheerful", "sad"]),
+            voice("en-GB-RyanNeural", Gender::Male, &[]),
+            voice("de-DE-SeraphinaMultilingualNeural", Gender::Female, &[]),
+        ]);
+        catalog.voices[1].voice_type = SynthesisVoiceType::OnlineStandard;
+
+        let short_names = |query: VoiceQuery| -> Vec<&str> {
+            catalog
+                .query(&query)
+                .iter()
+                .map(|v| v.short_name.as_str())
+                .collect()
+        };
+        assert_eq!(
+            short_names(VoiceQuery::new().locale("EN")),
+            vec!["en-US-JennyNeural", "en-GB-RyanNeural"]
+        );
+        assert_eq!(
+            short_names(VoiceQuery::new().gender(Gender::Female).style("Cheerful")),
+            vec!["en-US-JennyNeural"]
+        );
+        assert_eq!(
+            short_names(VoiceQuery::new().neural(false)),
+            vec!["en-GB-RyanNeural"]
+        );
+        assert_eq!(
+            short_names(VoiceQuery::new().multilingual(true)),
+            vec!["de-DE-SeraphinaMultilingualNeural"]
+        );
+        assert_eq!(catalog.locales(), vec!["de-DE", "en-GB", "en-US"]);
+        assert!(catalog.find("en-gb-ryanneural").is_some());
+        assert!(!catalog.is_expired(Duration::from_secs(60)));
+        catalog.fetched_at -= 120;
+        assert!(catalog.is_expired(Duration::from_secs(60)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn caches_catalog_on_disk() {
+        let path = std::env::temp_dir().join(format!("voice-catalog-{}.json", std::process::id()));
+        let catalog = VoiceCatalog::new(vec![voice("en-US-JennyNeural", Gender::Female, &[])]);
+        catalog.save(&path).unwrap();
+        assert_eq!(
+            VoiceCatalog::load(&path, Duration::from_secs(60)).unwrap(),
+            Some(catalog)
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            VoiceCatalog::load(&path, Duration::from_secs(60)).unwrap(),
+            None
+        );
+    }
+}
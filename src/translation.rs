@@ -0,0 +1,17 @@
+//! Package translation provides functionality for speech translation (including translated speech output) along with related configuration and event objects.
+mod speech_translation_config;
+mod translation_recognition_canceled_event;
+mod translation_recognition_event;
+mod translation_recognition_result;
+mod translation_recognizer;
+mod translation_synthesis_event;
+mod translation_synthesis_result;
+
+// re-export structs directly under translation module
+pub use self::speech_translation_config::SpeechTranslationConfig;
+pub use self::translation_recognition_canceled_event::TranslationRecognitionCanceledEvent;
+pub use self::translation_recognition_event::TranslationRecognitionEvent;
+pub use self::translation_recognition_result::TranslationRecognitionResult;
+pub use self::translation_recognizer::TranslationRecognizer;
+pub use self::translation_synthesis_event::TranslationSynthesisEvent;
+pub use self::translation_synthesis_result::TranslationSynthesisResult;
@@ -0,0 +1,217 @@
+use crate::common::PropertyId;
+use crate::error::{convert_err, Result};
+use crate::ffi::{
+    speech_translation_config_add_target_language,
+    speech_translation_config_from_authorization_token, speech_translation_config_from_endpoint,
+    speech_translation_config_from_host, speech_translation_config_from_subscription,
+    speech_translation_config_remove_target_language,
+    speech_translation_config_set_custom_model_category_id, SPXSPEECHCONFIGHANDLE,
+};
+use crate::speech::SpeechConfig;
+use std::ffi::CString;
+use std::mem::MaybeUninit;
+
+/// SpeechTranslationConfig defines configurations for translation with speech input.
+/// Wraps *SpeechConfig* so that all common speech settings remain available via *config*.
+#[derive(Debug)]
+pub struct SpeechTranslationConfig {
+    pub config: SpeechConfig,
+}
+
+impl From<SpeechTranslationConfig> for SpeechConfig {
+    fn from(stc: SpeechTranslationConfig) -> SpeechConfig {
+        stc.config
+    }
+}
+
+impl SpeechTranslationConfig {
+    /// Creates an instance of the speech translation config with specified subscription key and region.
+    pub fn from_subscription<S>(subscription: S, region: S) -> Result<SpeechTranslationConfig>
+    where
+        S: Into<Vec<u8>>,
+    {
+        let c_sub = CString::new(subscription)?;
+        let c_region = CString::new(region)?;
+
+        unsafe {
+            let mut handle: MaybeUninit<SPXSPEECHCONFIGHANDLE> = MaybeUninit::uninit();
+            let ret = speech_translation_config_from_subscription(
+                handle.as_mut_ptr(),
+                c_sub.as_ptr(),
+                c_region.as_ptr(),
+            );
+            convert_err(ret, "SpeechTranslationConfig::from_subscription error")?;
+            Ok(SpeechTranslationConfig {
+                config: SpeechConfig::from_handle(handle.assume_init())?,
+            })
+        }
+    }
+
+    /// Creates an instance of the speech translation config with specified authorization token and region.
+    /// Note: The caller needs to ensure that the authorization token is valid. Before the authorization token expires, the
+    /// caller needs to refresh it by setting the property on the created recognizer.
+    pub fn from_auth_token<S>(auth_token: S, region: S) -> Result<SpeechTranslationConfig>
+    where
+        S: Into<Vec<u8>>,
+    {
+        let c_auth_token = CString::new(auth_token)?;
+        let c_region = CString::new(region)?;
+
+        unsafe {
+            let mut handle: MaybeUninit<SPXSPEECHCONFIGHANDLE> = MaybeUninit::uninit();
+            let ret = speech_translation_config_from_authorization_token(
+                handle.as_mut_ptr(),
+                c_auth_token.as_ptr(),
+                c_region.as_ptr(),
+            );
+            convert_err(ret, "SpeechTranslationConfig::from_auth_token error")?;
+            Ok(SpeechTranslationConfig {
+                config: SpeechConfig::from_handle(handle.assume_init())?,
+            })
+        }
+    }
+
+    /// Creates an instance of the speech translation config with specified endpoint and subscription.
+    /// This method is intended only for users who use a non-standard service endpoint.
+    pub fn from_endpoint_with_subscription<S>(
+        endpoint: S,
+        subscription: S,
+    ) -> Result<SpeechTranslationConfig>
+    where
+        S: Into<Vec<u8>>,
+    {
+        let c_endpoint = CString::new(endpoint)?;
+        let c_subscription = CString::new(subscription)?;
+
+        unsafe {
+            let mut handle: MaybeUninit<SPXSPEECHCONFIGHANDLE> = MaybeUninit::uninit();
+            let ret = speech_translation_config_from_endpoint(
+                handle.as_mut_ptr(),
+                c_endpoint.as_ptr(),
+                c_subscription.as_ptr(),
+            );
+            convert_err(
+                ret,
+                "SpeechTranslationConfig::from_endpoint_with_subscription error",
+            )?;
+            Ok(SpeechTranslationConfig {
+                config: SpeechConfig::from_handle(handle.assume_init())?,
+            })
+        }
+    }
+
+    /// Creates an instance of the speech translation config with specified host and subscription.
+    /// This method is intended only for users who use a non-default service host.
+    pub fn from_host_with_subscription<S>(
+        host: S,
+        subscription: S,
+    ) -> Result<SpeechTranslationConfig>
+    where
+        S: Into<Vec<u8>>,
+    {
+        let c_host = CString::new(host)?;
+        let c_subscription = CString::new(subscription)?;
+
+        unsafe {
+            let mut handle: MaybeUninit<SPXSPEECHCONFIGHANDLE> = MaybeUninit::uninit();
+            let ret = speech_translation_config_from_host(
+                handle.as_mut_ptr(),
+                c_host.as_ptr(),
+                c_subscription.as_ptr(),
+            );
+            convert_err(
+                ret,
+                "SpeechTranslationConfig::from_host_with_subscription error",
+            )?;
+            Ok(SpeechTranslationConfig {
+                config: SpeechConfig::from_handle(handle.assume_init())?,
+            })
+        }
+    }
+
+    /// Adds a (text) target language for translation, specified in BCP-47 format (e.g. de-DE).
+    pub fn add_target_language(&mut self, language: &str) -> Result<()> {
+        let c_language = CString::new(language)?;
+        unsafe {
+            let ret = speech_translation_config_add_target_language(
+                self.config.handle.inner(),
+                c_language.as_ptr(),
+            );
+            convert_err(ret, "SpeechTranslationConfig.add_target_language error")?;
+            Ok(())
+        }
+    }
+
+    /// Removes a (text) target language for translation.
+    pub fn remove_target_language(&mut self, language: &str) -> Result<()> {
+        let c_language = CString::new(language)?;
+        unsafe {
+            let ret = speech_translation_config_remove_target_language(
+                self.config.handle.inner(),
+                c_language.as_ptr(),
+            );
+            convert_err(ret, "SpeechTranslationConfig.remove_target_language error")?;
+            Ok(())
+        }
+    }
+
+    /// Gets target languages for translation.
+    pub fn get_target_languages(&self) -> Result<Vec<String>> {
+        let languages = self
+            .config
+            .get_property(PropertyId::SpeechServiceConnectionTranslationToLanguages)?;
+        Ok(languages
+            .split(',')
+            .filter(|language| !language.is_empty())
+            .map(|language| language.to_owned())
+            .collect())
+    }
+
+    /// Sets the name of the voice used for translation synthesis (speech output).
+    /// Setting the voice enables *TranslationRecognizer::set_synthesizing_cb* events.
+    pub fn set_voice_name(&mut self, voice_name: String) -> Result<()> {
+        self.config.set_property(
+            PropertyId::SpeechServiceConnectionTranslationVoice,
+            voice_name,
+        )
+    }
+
+    /// Gets the name of the voice used for translation synthesis.
+    pub fn get_voice_name(&self) -> Result<String> {
+        self.config
+            .get_property(PropertyId::SpeechServiceConnectionTranslationVoice)
+    }
+
+    /// Sets a Category Id that will be passed to service. Category Id is used to find the custom model.
+    pub fn set_custom_model_category_id(&mut self, category_id: &str) -> Result<()> {
+        let c_category_id = CString::new(category_id)?;
+        unsafe {
+            let ret = speech_translation_config_set_custom_model_category_id(
+                self.config.handle.inner(),
+                c_category_id.as_ptr(),
+            );
+            convert_err(
+                ret,
+                "SpeechTranslationConfig.set_custom_model_category_id error",
+            )?;
+            Ok(())
+        }
+    }
+
+    /// Sets input language to the speech recognition, specified in BCP-47 format.
+    pub fn set_speech_recognition_language(&mut self, reco_lang: String) -> Result<()> {
+        self.config.set_speech_recognition_language(reco_lang)
+    }
+
+    pub fn get_speech_recognition_language(&self) -> Result<String> {
+        self.config.get_speech_recognition_language()
+    }
+
+    pub fn set_property(&mut self, id: PropertyId, value: String) -> Result<()> {
+        self.config.set_property(id, value)
+    }
+
+    pub fn get_property(&self, id: PropertyId) -> Result<String> {
+        self.config.get_property(id)
+    }
+}
@@ -0,0 +1,30 @@
+use crate::error::{convert_err, Result};
+use crate::ffi::{recognizer_recognition_event_get_result, SPXEVENTHANDLE, SPXRESULTHANDLE};
+use crate::speech::RecognitionEvent;
+use crate::translation::TranslationRecognitionResult;
+use log::*;
+use std::mem::MaybeUninit;
+
+/// Translation event extending *RecognitionEvent* passed into callbacks *set_recognizing_cb* and *set_recognized_cb*
+/// of *TranslationRecognizer*.
+#[derive(Debug)]
+pub struct TranslationRecognitionEvent {
+    pub base: RecognitionEvent,
+    pub result: TranslationRecognitionResult,
+}
+
+impl TranslationRecognitionEvent {
+    /// # Safety
+    /// `handle` must be a valid handle to a live translation recognition event.
+    pub unsafe fn from_handle(handle: SPXEVENTHANDLE) -> Result<TranslationRecognitionEvent> {
+        unsafe {
+            let base = RecognitionEvent::from_handle(handle)?;
+            let mut result_handle: MaybeUninit<SPXRESULTHANDLE> = MaybeUninit::uninit();
+            trace!("calling recognizer_recognition_event_get_result");
+            let ret = recognizer_recognition_event_get_result(handle, result_handle.as_mut_ptr());
+            convert_err(ret, "TranslationRecognitionEvent::from_handle error")?;
+            let result = TranslationRecognitionResult::from_handle(result_handle.assume_init())?;
+            Ok(TranslationRecognitionEvent { base, result })
+        }
+    }
+}
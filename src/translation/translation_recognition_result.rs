@@ -0,0 +1,99 @@
+use crate::error::{convert_err, Result};
+use crate::ffi::{
+    translation_text_result_get_translation, translation_text_result_get_translation_count,
+    SPXRESULTHANDLE,
+};
+use crate::speech::SpeechRecognitionResult;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+/// Error code returned by *translation_text_result_get_translation*
+/// when called with empty buffers in order to retrieve their sizes.
+const SPXERR_BUFFER_TOO_SMALL: usize = 0x019;
+
+/// Represents translation result contained within callback event *TranslationRecognitionEvent*.
+#[derive(Debug)]
+pub struct TranslationRecognitionResult {
+    pub base: SpeechRecognitionResult,
+    /// Translated texts keyed by target language.
+    pub translations: HashMap<String, String>,
+}
+
+impl TranslationRecognitionResult {
+    /// # Safety
+    /// `handle` must be a valid handle to a live translation recognition result.
+    pub unsafe fn from_handle(handle: SPXRESULTHANDLE) -> Result<TranslationRecognitionResult> {
+        unsafe {
+            let base = SpeechRecognitionResult::from_handle(handle)?;
+
+            let mut count = 0;
+            let ret = translation_text_result_get_translation_count(handle, &mut count);
+            convert_err(
+                ret,
+                "TranslationRecognitionResult::from_handle(get translation count) error",
+            )?;
+
+            let mut translations = HashMap::with_capacity(count);
+            for index in 0..count {
+                // first call only retrieves required buffer sizes
+                let mut language_size = 0;
+                let mut text_size = 0;
+                let ret = translation_text_result_get_translation(
+                    handle,
+                    index,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    &mut language_size,
+                    &mut text_size,
+                );
+                convert_size_query_err(ret)?;
+
+                let mut language_vec = vec![0u8; language_size + 1];
+                let mut text_vec = vec![0u8; text_size + 1];
+                let c_language: *mut c_char = &mut language_vec[..] as *const _ as *mut c_char;
+                let c_text: *mut c_char = &mut text_vec[..] as *const _ as *mut c_char;
+                let ret = translation_text_result_get_translation(
+                    handle,
+                    index,
+                    c_language,
+                    c_text,
+                    &mut language_size,
+                    &mut text_size,
+                );
+                convert_err(
+                    ret,
+                    "TranslationRecognitionResult::from_handle(get translation) error",
+                )?;
+                translations.insert(
+                    CStr::from_ptr(c_language).to_str()?.to_owned(),
+                    CStr::from_ptr(c_text).to_str()?.to_owned(),
+                );
+            }
+
+            Ok(TranslationRecognitionResult { base, translations })
+        }
+    }
+}
+
+/// Size query of non-empty translation answers *SPXERR_BUFFER_TOO_SMALL*, which is expected.
+fn convert_size_query_err(ret: usize) -> Result<()> {
+    if ret == SPXERR_BUFFER_TOO_SMALL {
+        return Ok(());
+    }
+    convert_err(
+        ret,
+        "TranslationRecognitionResult::from_handle(get translation size) error",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_query_accepts_buffer_too_small() {
+        assert!(convert_size_query_err(SPXERR_BUFFER_TOO_SMALL).is_ok());
+        assert!(convert_size_query_err(0).is_ok());
+    }
+}
@@ -0,0 +1,600 @@
+use crate::audio::AudioConfig;
+use crate::common::{PropertyCollection, PropertyId};
+use crate::error::{convert_err, Result};
+use crate::ffi::{
+    recognizer_async_handle_release, recognizer_canceled_set_callback,
+    recognizer_create_translation_recognizer_from_auto_detect_source_lang_config,
+    recognizer_create_translation_recognizer_from_config, recognizer_get_property_bag,
+    recognizer_handle_release, recognizer_recognize_once, recognizer_recognized_set_callback,
+    recognizer_recognizing_set_callback, recognizer_session_started_set_callback,
+    recognizer_session_stopped_set_callback, recognizer_speech_end_detected_set_callback,
+    recognizer_speech_start_detected_set_callback, recognizer_start_continuous_recognition_async,
+    recognizer_start_continuous_recognition_async_wait_for,
+    recognizer_stop_continuous_recognition_async,
+    recognizer_stop_continuous_recognition_async_wait_for, translator_add_target_language,
+    translator_remove_target_language, translator_synthesizing_audio_set_callback, SmartHandle,
+    SPXASYNCHANDLE, SPXEVENTHANDLE, SPXPROPERTYBAGHANDLE, SPXRECOHANDLE, SPXRESULTHANDLE,
+};
+use crate::speech::{AutoDetectSourceLanguageConfig, RecognitionEvent, SessionEvent};
+use crate::translation::{
+    SpeechTranslationConfig, TranslationRecognitionCanceledEvent, TranslationRecognitionEvent,
+    TranslationRecognitionResult, TranslationSynthesisEvent,
+};
+use log::*;
+use std::boxed::Box;
+use std::ffi::CString;
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::os::raw::c_void;
+
+/// TranslationRecognizer struct holds functionality for speech-to-text translation,
+/// optionally producing synthesized speech of the translation (see *SpeechTranslationConfig::set_voice_name*).
+pub struct TranslationRecognizer {
+    pub(crate) handle: SmartHandle<SPXRECOHANDLE>,
+    properties: PropertyCollection,
+    handle_async_start_continuous: Option<SmartHandle<SPXASYNCHANDLE>>,
+    handle_async_stop_continuous: Option<SmartHandle<SPXASYNCHANDLE>>,
+    session_started_cb: Option<Box<dyn Fn(SessionEvent) + Send>>,
+    session_stopped_cb: Option<Box<dyn Fn(SessionEvent) + Send>>,
+    speech_start_detected_cb: Option<Box<dyn Fn(RecognitionEvent) + Send>>,
+    speech_end_detected_cb: Option<Box<dyn Fn(RecognitionEvent) + Send>>,
+    canceled_cb: Option<Box<dyn Fn(TranslationRecognitionCanceledEvent) + Send>>,
+    recognizing_cb: Option<Box<dyn Fn(TranslationRecognitionEvent) + Send>>,
+    recognized_cb: Option<Box<dyn Fn(TranslationRecognitionEvent) + Send>>,
+    synthesizing_cb: Option<Box<dyn Fn(TranslationSynthesisEvent) + Send>>,
+}
+
+impl fmt::Debug for TranslationRecognizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TranslationRecognizer")
+            .field("handle", &self.handle)
+            .field("properties", &self.properties)
+            .finish()
+    }
+}
+
+impl TranslationRecognizer {
+    /// # Safety
+    /// `handle` must be a valid handle to a live translation recognizer.
+    unsafe fn from_handle(handle: SPXRECOHANDLE) -> Result<TranslationRecognizer> {
+        unsafe {
+            let mut prop_bag_handle: MaybeUninit<SPXPROPERTYBAGHANDLE> = MaybeUninit::uninit();
+            let ret = recognizer_get_property_bag(handle, prop_bag_handle.as_mut_ptr());
+            convert_err(ret, "TranslationRecognizer::from_handle error")?;
+
+            let property_bag = PropertyCollection::from_handle(prop_bag_handle.assume_init());
+
+            let result = TranslationRecognizer {
+                handle: SmartHandle::create(
+                    "TranslationRecognizer",
+                    handle,
+                    recognizer_handle_release,
+                ),
+                properties: property_bag,
+                handle_async_start_continuous: None,
+                handle_async_stop_continuous: None,
+                session_started_cb: None,
+                session_stopped_cb: None,
+                speech_start_detected_cb: None,
+                speech_end_detected_cb: None,
+                canceled_cb: None,
+                recognizing_cb: None,
+                recognized_cb: None,
+                synthesizing_cb: None,
+            };
+            Ok(result)
+        }
+    }
+
+    /// Creates translation recognizer from speech translation config and audio config.
+    pub fn from_config(
+        translation_config: SpeechTranslationConfig,
+        audio_config: AudioConfig,
+    ) -> Result<TranslationRecognizer> {
+        unsafe {
+            let mut handle: MaybeUninit<SPXRECOHANDLE> = MaybeUninit::uninit();
+            convert_err(
+                recognizer_create_translation_recognizer_from_config(
+                    handle.as_mut_ptr(),
+                    translation_config.config.handle.inner(),
+                    audio_config.handle.inner(),
+                ),
+                "TranslationRecognizer.from_config error",
+            )?;
+            TranslationRecognizer::from_handle(handle.assume_init())
+        }
+    }
+
+    /// Creates translation recognizer with automatic detection of the source language.
+    pub fn from_auto_detect_source_lang_config(
+        translation_config: SpeechTranslationConfig,
+        audio_config: AudioConfig,
+        lang_config: AutoDetectSourceLanguageConfig,
+    ) -> Result<TranslationRecognizer> {
+        unsafe {
+            let mut handle: MaybeUninit<SPXRECOHANDLE> = MaybeUninit::uninit();
+            convert_err(
+                recognizer_create_translation_recognizer_from_auto_detect_source_lang_config(
+                    handle.as_mut_ptr(),
+                    translation_config.config.handle.inner(),
+                    lang_config.handle.inner(),
+                    audio_config.handle.inner(),
+                ),
+                "TranslationRecognizer.from_auto_detect_source_lang_config error",
+            )?;
+            TranslationRecognizer::from_handle(handle.assume_init())
+        }
+    }
+
+    /// Adds target language for translation to running recognizer.
+    pub fn add_target_language(&mut self, language: &str) -> Result<()> {
+        let c_language = CString::new(language)?;
+        unsafe {
+            let ret = translator_add_target_language(self.handle.inner(), c_language.as_ptr());
+            convert_err(ret, "TranslationRecognizer.add_target_language error")?;
+            Ok(())
+        }
+    }
+
+    /// Removes target language for translation from running recognizer.
+    pub fn remove_target_language(&mut self, language: &str) -> Result<()> {
+        let c_language = CString::new(language)?;
+        unsafe {
+            let ret = translator_remove_target_language(self.handle.inner(), c_language.as_ptr());
+            convert_err(ret, "TranslationRecognizer.remove_target_language error")?;
+            Ok(())
+        }
+    }
+
+    /// Gets target languages for translation.
+    pub fn get_target_languages(&self) -> Result<Vec<String>> {
+        let languages = self.properties.get_property(
+            PropertyId::SpeechServiceConnectionTranslationToLanguages,
+            "",
+        )?;
+        Ok(languages
+            .split(',')
+            .filter(|language| !language.is_empty())
+            .map(|language| language.to_owned())
+            .collect())
+    }
+
+    /// Gets name of the voice used for translation synthesis.
+    pub fn get_voice_name(&self) -> Result<String> {
+        self.properties
+            .get_property(PropertyId::SpeechServiceConnectionTranslationVoice, "")
+    }
+
+    pub fn set_session_started_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(SessionEvent) + 'static + Send,
+    {
+        self.session_started_cb = Some(Box::new(f));
+        unsafe {
+            let ret = recognizer_session_started_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_session_started),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(ret, "TranslationRecognizer.set_session_started_cb error")?;
+            Ok(())
+        }
+    }
+
+    pub fn set_session_stopped_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(SessionEvent) + 'static + Send,
+    {
+        self.session_stopped_cb = Some(Box::new(f));
+        unsafe {
+            let ret = recognizer_session_stopped_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_session_stopped),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(ret, "TranslationRecognizer.set_session_stopped_cb error")?;
+            Ok(())
+        }
+    }
+
+    pub fn set_speech_start_detected_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(RecognitionEvent) + 'static + Send,
+    {
+        self.speech_start_detected_cb = Some(Box::new(f));
+        unsafe {
+            let ret = recognizer_speech_start_detected_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_speech_start_detected),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(
+                ret,
+                "TranslationRecognizer.set_speech_start_detected_cb error",
+            )?;
+            Ok(())
+        }
+    }
+
+    pub fn set_speech_end_detected_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(RecognitionEvent) + 'static + Send,
+    {
+        self.speech_end_detected_cb = Some(Box::new(f));
+        unsafe {
+            let ret = recognizer_speech_end_detected_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_speech_end_detected),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(
+                ret,
+                "TranslationRecognizer.set_speech_end_detected_cb error",
+            )?;
+            Ok(())
+        }
+    }
+
+    /// Canceled signals for events containing canceled recognition results
+    /// (indicating a recognition attempt that was canceled as a result or a direct cancellation request
+    /// or, alternatively, a transport or protocol failure).
+    pub fn set_canceled_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(TranslationRecognitionCanceledEvent) + 'static + Send,
+    {
+        self.canceled_cb = Some(Box::new(f));
+        unsafe {
+            let ret = recognizer_canceled_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_canceled),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(ret, "TranslationRecognizer.set_canceled_cb error")?;
+            Ok(())
+        }
+    }
+
+    pub fn set_recognizing_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(TranslationRecognitionEvent) + 'static + Send,
+    {
+        self.recognizing_cb = Some(Box::new(f));
+        unsafe {
+            trace!("calling recognizer_recognizing_set_callback");
+            let ret = recognizer_recognizing_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_recognizing),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(ret, "TranslationRecognizer.set_recognizing_cb error")?;
+            trace!("called recognizer_recognizing_set_callback");
+            Ok(())
+        }
+    }
+
+    pub fn set_recognized_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(TranslationRecognitionEvent) + 'static + Send,
+    {
+        self.recognized_cb = Some(Box::new(f));
+        unsafe {
+            trace!("calling recognizer_recognized_set_callback");
+            let ret = recognizer_recognized_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_recognized),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(ret, "TranslationRecognizer.set_recognized_cb error")?;
+            trace!("called recognizer_recognized_set_callback");
+            Ok(())
+        }
+    }
+
+    /// Synthesizing signals events containing chunks of synthesized translation audio.
+    /// Requires voice to be configured via *SpeechTranslationConfig::set_voice_name*.
+    /// Each utterance ends with event with reason *ResultReason::SynthesizingAudioCompleted*.
+    pub fn set_synthesizing_cb<F>(&mut self, f: F) -> Result<()>
+    where
+        F: Fn(TranslationSynthesisEvent) + 'static + Send,
+    {
+        self.synthesizing_cb = Some(Box::new(f));
+        unsafe {
+            let ret = translator_synthesizing_audio_set_callback(
+                self.handle.inner(),
+                Some(Self::cb_synthesizing),
+                self as *const _ as *mut c_void,
+            );
+            convert_err(ret, "TranslationRecognizer.set_synthesizing_cb error")?;
+            Ok(())
+        }
+    }
+
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_session_started(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("TranslationRecognizer::cb_session_started called");
+        let translation_recognizer = &mut *(pvContext as *mut TranslationRecognizer);
+        trace!("translation_recognizer {:?}", translation_recognizer);
+        if let Some(cb) = &translation_recognizer.session_started_cb {
+            trace!("session_started_cb defined");
+            match SessionEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!("TranslationRecognizer::cb_session_started error {:?}", err);
+                }
+            }
+        }
+    }
+
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_session_stopped(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("TranslationRecognizer::cb_session_stopped called");
+        let translation_recognizer = &mut *(pvContext as *mut TranslationRecognizer);
+        if let Some(cb) = &translation_recognizer.session_stopped_cb {
+            trace!("cb_session_stopped defined");
+            match SessionEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!("TranslationRecognizer::cb_session_stopped error {:?}", err);
+                }
+            }
+        }
+    }
+
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_speech_start_detected(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("TranslationRecognizer::cb_speech_start_detected called");
+        let translation_recognizer = &mut *(pvContext as *mut TranslationRecognizer);
+        trace!("translation_recognizer {:?}", translation_recognizer);
+        if let Some(cb) = &translation_recognizer.speech_start_detected_cb {
+            trace!("speech_start_detected_cb defined");
+            match RecognitionEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!(
+                        "TranslationRecognizer::cb_speech_start_detected error {:?}",
+                        err
+                    );
+                }
+            }
+        }
+    }
+
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_speech_end_detected(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("TranslationRecognizer::cb_speech_end_detected called");
+        let translation_recognizer = &mut *(pvContext as *mut TranslationRecognizer);
+        if let Some(cb) = &translation_recognizer.speech_end_detected_cb {
+            trace!("speech_end_detected_cb defined");
+            match RecognitionEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!(
+                        "TranslationRecognizer::cb_speech_end_detected error {:?}",
+                        err
+                    );
+                }
+            }
+        }
+    }
+
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_canceled(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("TranslationRecognizer::cb_canceled called");
+        let translation_recognizer = &mut *(pvContext as *mut TranslationRecognizer);
+        if let Some(cb) = &translation_recognizer.canceled_cb {
+            trace!("canceled_cb defined");
+            match TranslationRecognitionCanceledEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!("TranslationRecognizer::cb_canceled error {:?}", err);
+                }
+            }
+        }
+    }
+
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_recognizing(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("TranslationRecognizer::cb_recognizing called");
+        let translation_recognizer = &mut *(pvContext as *mut TranslationRecognizer);
+        trace!("translation_recognizer {:?}", translation_recognizer);
+        if let Some(cb) = &translation_recognizer.recognizing_cb {
+            trace!("recognizing_cb defined");
+            match TranslationRecognitionEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!("TranslationRecognizer::cb_recognizing error {:?}", err);
+                }
+            }
+        }
+    }
+
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_recognized(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("TranslationRecognizer::cb_recognized called");
+        let translation_recognizer = &mut *(pvContext as *mut TranslationRecognizer);
+        if let Some(cb) = &translation_recognizer.recognized_cb {
+            trace!("recognized_cb defined");
+            match TranslationRecognitionEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!("TranslationRecognizer::cb_recognized error {:?}", err);
+                }
+            }
+        }
+    }
+
+    #[allow(non_snake_case)]
+    #[allow(unused_variables)]
+    unsafe extern "C" fn cb_synthesizing(
+        hreco: SPXRECOHANDLE,
+        hevent: SPXEVENTHANDLE,
+        pvContext: *mut c_void,
+    ) {
+        trace!("TranslationRecognizer::cb_synthesizing called");
+        let translation_recognizer = &mut *(pvContext as *mut TranslationRecognizer);
+        if let Some(cb) = &translation_recognizer.synthesizing_cb {
+            trace!("synthesizing_cb defined");
+            match TranslationSynthesisEvent::from_handle(hevent) {
+                Ok(event) => {
+                    trace!("calling cb with event {:?}", event);
+                    cb(event);
+                }
+                Err(err) => {
+                    error!("TranslationRecognizer::cb_synthesizing error {:?}", err);
+                }
+            }
+        }
+    }
+
+    /// Starts speech translation, and returns after a single utterance is recognized and translated.
+    /// The end of a single utterance is determined by listening for silence at the end or until a maximum
+    /// of 15 seconds of audio is processed.  The task returns the recognition text as result.
+    /// Note: Since RecognizeOnceAsync() returns only a single utterance, it is suitable only for single
+    /// shot recognition like command or query.
+    /// For long-running multi-utterance recognition, use StartContinuousRecognitionAsync() instead.
+    pub async fn recognize_once_async(&mut self) -> Result<TranslationRecognitionResult> {
+        unsafe {
+            let mut handle_result: MaybeUninit<SPXRESULTHANDLE> = MaybeUninit::uninit();
+            let ret = recognizer_recognize_once(self.handle.inner(), handle_result.as_mut_ptr());
+            convert_err(ret, "TranslationRecognizer.recognize_once_async error")?;
+            TranslationRecognitionResult::from_handle(handle_result.assume_init())
+        }
+    }
+
+    /// Asynchronously initiates continuous speech recognition operation.
+    pub async fn start_continuous_recognition_async(&mut self) -> Result<()> {
+        unsafe {
+            let mut handle_async_start_continuous: MaybeUninit<SPXASYNCHANDLE> =
+                MaybeUninit::uninit();
+            let mut ret = recognizer_start_continuous_recognition_async(
+                self.handle.inner(),
+                handle_async_start_continuous.as_mut_ptr(),
+            );
+            convert_err(
+                ret,
+                "TranslationRecognizer.recognizer_start_continuous_recognition_async error",
+            )?;
+            self.handle_async_start_continuous = Some(SmartHandle::create(
+                "handle_async_start_continuous",
+                handle_async_start_continuous.assume_init(),
+                recognizer_async_handle_release,
+            ));
+
+            ret = recognizer_start_continuous_recognition_async_wait_for(
+                handle_async_start_continuous.assume_init(),
+                u32::MAX,
+            );
+            convert_err(
+                ret,
+                "TranslationRecognizer.recognizer_start_continuous_recognition_async_wait_for error",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Asynchronously terminates ongoing continuous speech recognition operation.
+    pub async fn stop_continuous_recognition_async(&mut self) -> Result<()> {
+        unsafe {
+            let mut handle_async_stop_continuous: MaybeUninit<SPXASYNCHANDLE> =
+                MaybeUninit::uninit();
+            let mut ret = recognizer_stop_continuous_recognition_async(
+                self.handle.inner(),
+                handle_async_stop_continuous.as_mut_ptr(),
+            );
+            convert_err(
+                ret,
+                "TranslationRecognizer.recognizer_stop_continuous_recognition_async error",
+            )?;
+            self.handle_async_stop_continuous = Some(SmartHandle::create(
+                "handle_async_stop_continuous",
+                handle_async_stop_continuous.assume_init(),
+                recognizer_async_handle_release,
+            ));
+
+            ret = recognizer_stop_continuous_recognition_async_wait_for(
+                handle_async_stop_continuous.assume_init(),
+                u32::MAX,
+            );
+            convert_err(
+                ret,
+                "TranslationRecognizer.recognizer_stop_continuous_recognition_async_wait_for error",
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn get_endpoint_id(&self) -> Result<String> {
+        self.properties
+            .get_property(PropertyId::SpeechServiceConnectionEndpointId, "")
+    }
+
+    pub fn get_auth_token(&self) -> Result<String> {
+        self.properties
+            .get_property(PropertyId::SpeechServiceAuthorizationToken, "")
+    }
+
+    /// Sets the authorization token that will be used for connecting to the service.
+    /// Note: The caller needs to ensure that the authorization token is valid. Before the authorization token
+    /// expires, the caller needs to refresh it by calling this setter with a new valid token.
+    /// Otherwise, the recognizer will encounter errors during recognition.
+    pub fn set_auth_token(&mut self, token: &str) -> Result<()> {
+        self.properties
+            .set_property(PropertyId::SpeechServiceAuthorizationToken, token)
+    }
+}
@@ -0,0 +1,55 @@
+use crate::error::{convert_err, Result};
+use crate::ffi::{
+    recognizer_event_handle_release, recognizer_recognition_event_get_result,
+    recognizer_session_event_get_session_id, SmartHandle, SPXEVENTHANDLE, SPXRESULTHANDLE,
+};
+use crate::translation::TranslationSynthesisResult;
+use std::ffi::CStr;
+use std::mem::MaybeUninit;
+
+/// Event passed into callback *set_synthesizing_cb* of *TranslationRecognizer*.
+/// Carries chunk of synthesized translation audio.
+#[derive(Debug)]
+pub struct TranslationSynthesisEvent {
+    pub handle: SmartHandle<SPXEVENTHANDLE>,
+    pub session_id: String,
+    pub result: TranslationSynthesisResult,
+}
+
+impl TranslationSynthesisEvent {
+    /// # Safety
+    /// `handle` must be a valid handle to a live translation synthesis event.
+    pub unsafe fn from_handle(handle: SPXEVENTHANDLE) -> Result<TranslationSynthesisEvent> {
+        unsafe {
+            let mut c_buf = [0; 37];
+            let mut ret = recognizer_session_event_get_session_id(
+                handle,
+                c_buf.as_mut_ptr(),
+                c_buf.len() as u32,
+            );
+            convert_err(
+                ret,
+                "TranslationSynthesisEvent::from_handle(get session id) error",
+            )?;
+            let session_id = CStr::from_ptr(c_buf.as_ptr()).to_str()?.to_owned();
+
+            let mut result_handle: MaybeUninit<SPXRESULTHANDLE> = MaybeUninit::uninit();
+            ret = recognizer_recognition_event_get_result(handle, result_handle.as_mut_ptr());
+            convert_err(
+                ret,
+                "TranslationSynthesisEvent::from_handle(get result) error",
+            )?;
+            let result = TranslationSynthesisResult::from_handle(result_handle.assume_init())?;
+
+            Ok(TranslationSynthesisEvent {
+                handle: SmartHandle::create(
+                    "TranslationSynthesisEvent",
+                    handle,
+                    recognizer_event_handle_release,
+                ),
+                session_id,
+                result,
+            })
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::common::ResultReason;
+use crate::error::{convert_err, Result};
+use crate::ffi::{
+    recognizer_result_handle_release, result_get_reason,
+    translation_synthesis_result_get_audio_data, SmartHandle, SPXRESULTHANDLE,
+};
+use std::fmt;
+
+/// Error code returned by *translation_synthesis_result_get_audio_data*
+/// when called with empty buffer in order to retrieve audio size.
+const SPXERR_BUFFER_TOO_SMALL: usize = 0x019;
+
+/// Represents chunk of translated audio contained within callback event *TranslationSynthesisEvent*.
+pub struct TranslationSynthesisResult {
+    pub handle: SmartHandle<SPXRESULTHANDLE>,
+    /// *ResultReason::SynthesizingAudio* while audio is being produced,
+    /// *ResultReason::SynthesizingAudioCompleted* (with empty audio) once the utterance is complete.
+    pub reason: ResultReason,
+    pub audio_data: Vec<u8>,
+}
+
+impl fmt::Debug for TranslationSynthesisResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TranslationSynthesisResult")
+            .field("reason", &self.reason)
+            .field("audio_data_len", &self.audio_data.len())
+            .finish()
+    }
+}
+
+impl TranslationSynthesisResult {
+    /// # Safety
+    /// `handle` must be a valid handle to a live translation synthesis result.
+    pub unsafe fn from_handle(handle: SPXRESULTHANDLE) -> Result<TranslationSynthesisResult> {
+        unsafe {
+            let mut reason = 0;
+            let mut ret = result_get_reason(handle, &mut reason);
+            convert_err(
+                ret,
+                "TranslationSynthesisResult::from_handle(result_get_reason) error",
+            )?;
+
+            let mut audio_length: usize = 0;
+            ret = translation_synthesis_result_get_audio_data(
+                handle,
+                std::ptr::null_mut(),
+                &mut audio_length,
+            );
+            if ret != SPXERR_BUFFER_TOO_SMALL {
+                convert_err(
+                    ret,
+                    "TranslationSynthesisResult::from_handle(get audio size) error",
+                )?;
+            }
+
+            let mut audio_data = vec![0u8; audio_length];
+            if audio_length > 0 {
+                ret = translation_synthesis_result_get_audio_data(
+                    handle,
+                    audio_data.as_mut_ptr(),
+                    &mut audio_length,
+                );
+                convert_err(
+                    ret,
+                    "TranslationSynthesisResult::from_handle(get audio data) error",
+                )?;
+                audio_data.truncate(audio_length);
+            }
+
+            Ok(TranslationSynthesisResult {
+                handle: SmartHandle::create(
+                    "TranslationSynthesisResult",
+                    handle,
+                    recognizer_result_handle_release,
+                ),
+                reason: reason.into(),
+                audio_data,
+            })
+        }
+    }
+}
@@ -1,14 +1,9 @@
 use cognitive_services_speech_sdk_rs as msspeech;
 use cognitive_services_speech_sdk_rs::speech::PhraseListGrammar;
 use log::*;
-use rust_embed::Embed;
 use std::env;
 use std::path::PathBuf;
 
-#[derive(Embed)]
-#[folder = "examples/sample_files"]
-struct Asset;
-
 #[tokio::test]
 async fn speech_to_text() {
     let current_dir = std::env::current_dir().expect("Failed to get current directory");